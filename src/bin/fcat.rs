@@ -5,55 +5,190 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use std::io;
+use std::process;
 use std::str::FromStr;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use structopt::StructOpt;
 
 use flatcat::cli_parser::Opts;
 use flatcat::file_extension::FILE_EXTENSION_LIST;
+use flatcat::file_name::FILE_NAME_LIST;
 use flatcat::output::Output;
-use flatcat::{FlatCat, FlatCatOpts, Format, FormatHint, Input, OutputOpts};
+use flatcat::{
+    ColorChoice, DocPrefix, FlatCat, FlatCatOpts, FlatDiff, Format, FormatHint, IndexStyle, Input, OutputOpts,
+    OutputStyle, PathFilter, PathPattern, PathStyle, Theme, UnflatCat,
+};
 
-fn main() -> Result<()> {
+fn main() {
     let opts = Opts::from_args();
 
+    if let Err(err) = run(&opts) {
+        eprintln!("Error: {:?}", err);
+        // Like diff(1), diff mode exits with 1 for differences and with 2 for errors
+        process::exit(if opts.diff { 2 } else { 1 });
+    }
+}
+
+fn run(opts: &Opts) -> Result<()> {
     if opts.type_list {
         print_type_list();
         return Ok(());
     }
 
+    if opts.unflatten {
+        return unflatten(opts).context("failed to unflatten file");
+    }
+
+    let output_style = if opts.env {
+        OutputStyle::Env
+    } else {
+        OutputStyle::from_str(&opts.output).context("failed to parse output option")?
+    };
+    let path_style = PathStyle::from_str(&opts.path_style).context("failed to parse path style option")?;
+    let index_style = IndexStyle::from_str(&opts.index_style).context("failed to parse index style option")?;
+    let color = if opts.no_color {
+        ColorChoice::Never
+    } else {
+        ColorChoice::from_str(&opts.color).context("failed to parse color option")?
+    };
+    let colorful = color.is_colorful();
+    let theme = if colorful {
+        Theme::load(opts.theme.as_deref()).context("failed to load color theme")?
+    } else {
+        Theme::default()
+    };
     let output_opts = OutputOpts::new()
-        .with_color(!opts.no_color)
+        .with_style(output_style)
+        .with_path_style(path_style)
+        .with_separator(opts.separator.as_str())
+        .with_index_style(index_style)
+        .with_one_based(opts.one_based)
+        .with_root(opts.root.clone())
+        .with_color(colorful)
+        .with_theme(theme)
+        .with_depth_colors(opts.depth_colors)
         .with_null(!opts.no_null)
+        .with_empty(!opts.no_empty)
         .with_quotes(!opts.no_quotes)
+        .with_escape(!opts.no_escape)
+        .with_export(opts.export)
+        .with_header(!opts.output_no_header)
+        .with_delimiter(opts.delimiter.as_str())
+        .with_null_data(opts.null_data)
         .with_numbers(opts.numbers)
         .with_end_of_lines(opts.end_of_line);
     let output = Output::from_stdout(output_opts);
 
-    let flatcat_opts = FlatCatOpts::new().with_flatten(opts.flatten);
+    let doc_prefix = DocPrefix::from_str(&opts.doc_prefix).context("failed to parse document prefix option")?;
+    let filter = path_filter(opts).context("failed to parse path pattern option")?;
+    let csv_delimiter = csv_delimiter(opts.csv_delimiter.as_deref()).context("failed to parse csv delimiter option")?;
+    let flatcat_opts = FlatCatOpts::new()
+        .with_flatten(opts.flatten)
+        .with_doc_prefix(doc_prefix)
+        .with_filter(filter)
+        .with_csv_header(!opts.csv_no_header)
+        .with_csv_delimiter(csv_delimiter)
+        .with_infer_types(opts.infer_types)
+        .with_skip_handler(warn_skipped);
+
+    if opts.diff {
+        let differences = diff(opts, flatcat_opts, output).context("failed to diff files")?;
+        if differences {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
     let mut flatcat = FlatCat::new(flatcat_opts, output).context("failed to instantiate FlatCat")?;
 
-    cats(&opts, &mut flatcat).context("failed to cat file")?;
+    cats(opts, &mut flatcat).context("failed to cat file")?;
 
     Ok(())
 }
 
+/// Warns about malformed records without aborting, so streams keep being flattened
+fn warn_skipped(record: usize, err: &flatcat::Error) {
+    match std::error::Error::source(err) {
+        Some(source) => eprintln!("fcat: skipping malformed record {}: {}: {}", record, err, source),
+        None => eprintln!("fcat: skipping malformed record {}: {}", record, err),
+    }
+}
+
+fn path_filter(opts: &Opts) -> Result<PathFilter> {
+    let select = opts
+        .select
+        .iter()
+        .map(|x| PathPattern::from_str(x))
+        .collect::<flatcat::Result<Vec<_>>>()?;
+    let exclude = opts
+        .exclude
+        .iter()
+        .map(|x| PathPattern::from_str(x))
+        .collect::<flatcat::Result<Vec<_>>>()?;
+
+    Ok(PathFilter::new().with_select(select).with_exclude(exclude))
+}
+
+/// Parses a single ASCII character; `\t` is accepted for tab
+fn csv_delimiter(delimiter: Option<&str>) -> Result<Option<u8>> {
+    match delimiter {
+        None => Ok(None),
+        Some("\\t") => Ok(Some(b'\t')),
+        Some(x) if x.len() == 1 && x.is_ascii() => Ok(Some(x.as_bytes()[0])),
+        Some(x) => Err(anyhow!("delimiter '{}' is not a single ASCII character", x)),
+    }
+}
+
 fn print_type_list() {
     for (typ, extensions) in FILE_EXTENSION_LIST {
-        println!("{}: {}", typ, extensions);
+        match FILE_NAME_LIST.iter().find(|(x, _)| x == typ) {
+            Some((_, names)) => println!("{}: {}, {}", typ, extensions, names),
+            None => println!("{}: {}", typ, extensions),
+        }
+    }
+}
+
+/// Diff exactly two given files
+fn diff(opts: &Opts, flatcat_opts: FlatCatOpts, output: Output) -> Result<bool> {
+    if opts.files.len() != 2 {
+        return Err(anyhow!("diff requires exactly two files, but got {}", opts.files.len()));
     }
+    let mut flatdiff = FlatDiff::new(flatcat_opts, output).context("failed to instantiate FlatDiff")?;
+    let left = with_format(opts.format.as_ref(), create_input(&opts.files[0]))?;
+    let right = with_format(opts.format.as_ref(), create_input(&opts.files[1]))?;
+    let differences = flatdiff.diff(left, right)?;
+
+    Ok(differences)
+}
+
+/// Unflatten all given files into one document; if non given, read from stdin
+fn unflatten(opts: &Opts) -> Result<()> {
+    let to = Format::from_str(&opts.to).context("failed to parse format option")?;
+    let mut unflatcat = UnflatCat::new(to);
+
+    if opts.files.is_empty() {
+        unflatcat.read(create_input("-"))?;
+    } else {
+        for file in &opts.files {
+            unflatcat.read(create_input(file))?;
+        }
+    }
+    unflatcat.write(io::stdout())?;
+
+    Ok(())
 }
 
 /// Cat all given files; if non given, read from stdin
-fn cats(opts: &Opts, mut flatcat: &mut FlatCat) -> Result<()> {
+fn cats(opts: &Opts, flatcat: &mut FlatCat) -> Result<()> {
     if opts.files.is_empty() {
         let input = create_input("-");
-        cat(opts.format.as_ref(), &mut flatcat, input)?
+        cat(opts.format.as_ref(), flatcat, input)?
     } else {
         for file in &opts.files {
             let input = create_input(file);
-            cat(opts.format.as_ref(), &mut flatcat, input)?
+            cat(opts.format.as_ref(), flatcat, input)?
         }
     }
 
@@ -70,12 +205,20 @@ fn create_input(path: &str) -> Input {
 }
 
 /// Cat single file
-fn cat(format: Option<&String>, flatcat: &mut FlatCat, mut input: Input) -> Result<()> {
-    if let Some(format) = format {
-        let format = Format::from_str(format).context("failed to parse format option")?;
-        input = input.with_format_hint(FormatHint::hint(format));
-    }
+fn cat(format: Option<&String>, flatcat: &mut FlatCat, input: Input) -> Result<()> {
+    let input = with_format(format, input)?;
     flatcat.cat(input)?;
 
     Ok(())
 }
+
+/// Set format hint if format is given
+fn with_format(format: Option<&String>, input: Input) -> Result<Input> {
+    match format {
+        Some(format) => {
+            let format = Format::from_str(format).context("failed to parse format option")?;
+            Ok(input.with_format_hint(FormatHint::hint(format)))
+        }
+        None => Ok(input),
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::io::Read;
+
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::catter::{Catter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{FlatCatOpts, Result};
+
+/// Flattens CSV and TSV one row at a time like `[0].name`; without header, rows are arrays like `[0][1]`
+#[derive(Debug)]
+pub struct CsvCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+    /// Delimiter of the format unless set by `FlatCatOpts`
+    delimiter: u8,
+}
+
+impl<'a> CsvCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter, delimiter: u8) -> CsvCatter<'b> {
+        CsvCatter {
+            opts,
+            output,
+            delimiter,
+        }
+    }
+
+    fn csv<R: Read>(&mut self, read: R) -> Result<()> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(self.opts.csv_delimiter.unwrap_or(self.delimiter))
+            .has_headers(self.opts.csv_header)
+            .flexible(true)
+            .from_reader(read);
+        let header = if self.opts.csv_header {
+            Some(reader.headers()?.clone())
+        } else {
+            None
+        };
+
+        let mut path = KeyPath::new();
+        let mut record = StringRecord::new();
+        let mut rows = 0;
+        while reader.read_record(&mut record)? {
+            if rows == 0 && self.opts.is_selected(&path) {
+                self.output.start_array(&path);
+            }
+            path.push_index(rows);
+            self.row(&mut path, header.as_ref(), &record);
+            path.pop();
+            rows += 1;
+        }
+        if rows == 0 && self.opts.is_selected(&path) {
+            self.output.empty_array(&path);
+        }
+
+        Ok(())
+    }
+
+    fn row(&mut self, path: &mut KeyPath, header: Option<&StringRecord>, record: &StringRecord) {
+        if self.opts.is_selected(path) {
+            match header {
+                Some(_) => self.output.start_object(path),
+                None => self.output.start_array(path),
+            }
+        }
+        for (i, value) in record.iter().enumerate() {
+            // Fields of rows longer than the header are named by their position
+            match header {
+                Some(header) => path.push(
+                    header
+                        .get(i)
+                        .map(str::to_string)
+                        .unwrap_or_else(|| i.to_string())
+                        .as_str(),
+                ),
+                None => path.push_index(i),
+            }
+            if self.opts.is_selected(path) {
+                self.value(path, value);
+            }
+            path.pop();
+        }
+    }
+
+    /// Prints JSON numbers, booleans, and empty fields as such if type inference is enabled; numbers are printed
+    /// verbatim and leading zeros are not valid JSON, so zip codes like `01234` stay strings
+    fn value(&mut self, path: &KeyPath, value: &str) {
+        if !self.opts.infer_types {
+            self.output.string(path, value)
+        } else if value.is_empty() {
+            self.output.null(path)
+        } else if serde_json::from_str::<serde_json::Number>(value).is_ok() {
+            self.output.number(path, value)
+        } else if let Ok(x) = value.parse::<bool>() {
+            self.output.bool(path, x)
+        } else {
+            self.output.string(path, value)
+        }
+    }
+}
+
+impl<'a> Catter for CsvCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        self.csv(read)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::{json, Value};
+    use spectral::prelude::*;
+
+    use crate::OutputOpts;
+
+    fn csv(opts: FlatCatOpts, csv: &'static str) -> Vec<(String, Value)> {
+        let mut output = OutputWriter::leaf_collector(&OutputOpts::new());
+        CsvCatter::new(&opts, &mut output, b',')
+            .cat(&mut csv.as_bytes())
+            .expect("failed to cat csv");
+        output
+            .into_leaves()
+            .into_iter()
+            .map(|(path, value)| (path.to_string(), value))
+            .collect()
+    }
+
+    fn leaves(leaves: &[(&str, Value)]) -> Vec<(String, Value)> {
+        leaves
+            .iter()
+            .map(|(path, value)| (path.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn header() {
+        let res = csv(FlatCatOpts::new(), "name,age\nLukas,42\n");
+
+        let expected = leaves(&[("[0].name", json!("Lukas")), ("[0].age", json!("42"))]);
+        asserting("columns are named by header")
+            .that(&res)
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn no_header() {
+        let res = csv(FlatCatOpts::new().with_csv_header(false), "name,age\nLukas,42\n");
+
+        let expected = leaves(&[
+            ("[0][0]", json!("name")),
+            ("[0][1]", json!("age")),
+            ("[1][0]", json!("Lukas")),
+            ("[1][1]", json!("42")),
+        ]);
+        asserting("rows are arrays").that(&res).is_equal_to(expected);
+    }
+
+    #[test]
+    fn short_and_long_rows() {
+        let res = csv(FlatCatOpts::new(), "name,age\nLukas\nAnna,23,admin\n");
+
+        let expected = leaves(&[
+            ("[0].name", json!("Lukas")),
+            ("[1].name", json!("Anna")),
+            ("[1].age", json!("23")),
+            ("[1].2", json!("admin")),
+        ]);
+        asserting("missing fields are omitted and extra fields named by position")
+            .that(&res)
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn infer_types() {
+        let res = csv(
+            FlatCatOpts::new().with_infer_types(true),
+            "zip,age,admin,name,note\n01234,42,true,Lukas,\n",
+        );
+
+        let expected = leaves(&[
+            ("[0].zip", json!("01234")),
+            ("[0].age", json!(42)),
+            ("[0].admin", json!(true)),
+            ("[0].name", json!("Lukas")),
+            ("[0].note", Value::Null),
+        ]);
+        asserting("numbers, booleans, and nulls are inferred")
+            .that(&res)
+            .is_equal_to(expected);
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::io::Read;
+
+use serde_json::{Map, Value};
+
+use crate::catter::{Catter, JsonCatter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{Error, FlatCatOpts, Result};
+
+/// Flattens `.env` files as read by dotenv libraries and docker compose
+#[derive(Debug)]
+pub struct DotenvCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+}
+
+impl<'a> DotenvCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter) -> DotenvCatter<'b> {
+        DotenvCatter { opts, output }
+    }
+
+    fn dotenv(&mut self, dotenv: Map<String, Value>) -> Result<()> {
+        let mut path = KeyPath::new();
+
+        let mut catter = JsonCatter::new(self.opts, self.output);
+        catter.do_json(&mut path, Value::Object(dotenv))
+    }
+}
+
+impl<'a> Catter for DotenvCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        let mut buffer = String::new();
+        read.read_to_string(&mut buffer)?;
+        let dotenv = parse_dotenv(&buffer)?;
+
+        self.dotenv(dotenv)?;
+
+        Ok(())
+    }
+}
+
+/// Parses `KEY=value` assignments optionally prefixed by `export`.
+///
+/// Single quoted values are literal, double quoted values decode `\n`, `\t`, `\"`, and `\\`, and both may span
+/// lines. Unquoted values end at a ` #` comment. Variables like `${HOME}` are not expanded.
+fn parse_dotenv(dotenv: &str) -> Result<Map<String, Value>> {
+    let mut root = Map::new();
+    let mut rest = dotenv;
+
+    while !rest.is_empty() {
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        let line = rest[..line_end].trim_start();
+        if line.trim().is_empty() || line.starts_with('#') {
+            rest = rest.get(line_end + 1..).unwrap_or("");
+            continue;
+        }
+
+        let assignment = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| dotenv_error(line, "missing '=' between key and value"))?;
+        let key = key.trim();
+        if key.is_empty() || key.chars().any(char::is_whitespace) {
+            return Err(dotenv_error(line, "invalid key"));
+        }
+
+        // Quoted values may continue on the following lines, so they are parsed from the remaining input
+        let value = value.trim_start();
+        let remaining = &rest[line_end - value.len()..];
+        let (value, len) = match remaining.chars().next() {
+            Some('\'') => single_quoted(&remaining[1..]).ok_or_else(|| dotenv_error(line, "unclosed single quote"))?,
+            Some('"') => double_quoted(&remaining[1..]).ok_or_else(|| dotenv_error(line, "unclosed double quote"))?,
+            _ => {
+                let end = value.find(" #").unwrap_or(value.len());
+                (value[..end].trim().to_string(), value.len())
+            }
+        };
+        rest = remaining[len..].split_once('\n').map(|(_, x)| x).unwrap_or("");
+
+        root.insert(key.to_string(), Value::String(value));
+    }
+
+    Ok(root)
+}
+
+/// Returns the value up to the closing quote and the length including both quotes
+fn single_quoted(s: &str) -> Option<(String, usize)> {
+    let end = s.find('\'')?;
+    Some((s[..end].to_string(), end + 2))
+}
+
+/// Returns the unescaped value up to the closing quote and the length including both quotes
+fn double_quoted(s: &str) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, i + 2)),
+            '\\' => match chars.next()? {
+                (_, 'n') => value.push('\n'),
+                (_, 't') => value.push('\t'),
+                (_, 'r') => value.push('\r'),
+                (_, c @ '"') | (_, c @ '\\') => value.push(c),
+                (_, c) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+            },
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+fn dotenv_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "Dotenv",
+        why: why.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use spectral::prelude::*;
+
+    #[test]
+    fn dotenv() {
+        let dotenv = r#"
+# comment
+export NODE_ENV=production
+PORT = 3000 # comment
+GREETING="Hello \"World\"\n"
+PASSWORD='p#ss\word'
+CERT="-----BEGIN-----
+abc
+-----END-----"
+EMPTY=
+URL=http://localhost/#anchor
+"#;
+
+        let res = parse_dotenv(dotenv).map(Value::Object);
+
+        let expected = json!({
+            "NODE_ENV": "production",
+            "PORT": "3000",
+            "GREETING": "Hello \"World\"\n",
+            "PASSWORD": "p#ss\\word",
+            "CERT": "-----BEGIN-----\nabc\n-----END-----",
+            "EMPTY": "",
+            "URL": "http://localhost/#anchor",
+        });
+        asserting("dotenv is parsed successfully")
+            .that(&res)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn unclosed_quote() {
+        let res = parse_dotenv("KEY=\"value\nOTHER=1\n");
+
+        asserting("unclosed quote fails").that(&res.is_err()).is_true();
+    }
+}
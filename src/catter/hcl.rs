@@ -0,0 +1,189 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::io::Read;
+
+use hcl::{Block, Body, Expression, ObjectKey, Structure, TemplateExpr};
+use serde_json::{Map, Value};
+
+use crate::catter::{Catter, JsonCatter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{Error, FlatCatOpts, Result};
+
+/// Flattens HCL like Terraform `.tf` and `.tfvars` files.
+///
+/// Blocks are keyed by their type and labels like `.resource.aws_instance.web.ami` and repeated blocks become
+/// arrays. Expressions are not evaluated, but printed as written, e.g., `var.region`; templates like
+/// `"${var.name}-web"` are printed without quotes.
+#[derive(Debug)]
+pub struct HclCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+}
+
+impl<'a> HclCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter) -> HclCatter<'b> {
+        HclCatter { opts, output }
+    }
+
+    fn hcl(&mut self, hcl: Map<String, Value>) -> Result<()> {
+        let mut path = KeyPath::new();
+
+        let mut catter = JsonCatter::new(self.opts, self.output);
+        catter.do_json(&mut path, Value::Object(hcl))
+    }
+}
+
+impl<'a> Catter for HclCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        let mut buffer = String::new();
+        read.read_to_string(&mut buffer)?;
+        let body = hcl::parse(&buffer)?;
+        let hcl = body_to_json(body)?;
+
+        self.hcl(hcl)?;
+
+        Ok(())
+    }
+}
+
+fn body_to_json(body: Body) -> Result<Map<String, Value>> {
+    let mut map = Map::new();
+
+    for structure in body {
+        match structure {
+            Structure::Attribute(attribute) => {
+                map.insert(attribute.key.to_string(), expression_to_json(attribute.expr));
+            }
+            Structure::Block(block) => insert_block(&mut map, block)?,
+        }
+    }
+
+    Ok(map)
+}
+
+/// Inserts the body of the block below its type and labels; the bodies of repeated blocks are collected in an array
+fn insert_block(map: &mut Map<String, Value>, block: Block) -> Result<()> {
+    let mut keys = vec![block.identifier.to_string()];
+    keys.extend(block.labels.iter().map(|x| x.as_str().to_string()));
+    let body = Value::Object(body_to_json(block.body)?);
+
+    let mut table = map;
+    let (last, parents) = keys.split_last().expect("block has an identifier");
+    for key in parents {
+        table = table
+            .entry(key.as_str())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| hcl_error(&keys.join("."), "block conflicts with repeated block or attribute"))?;
+    }
+    match table.get_mut(last) {
+        Some(Value::Array(bodies)) => bodies.push(body),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, body]);
+        }
+        None => {
+            table.insert(last.to_string(), body);
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts literal values and keeps all other expressions as written
+fn expression_to_json(expr: Expression) -> Value {
+    match expr {
+        Expression::Null => Value::Null,
+        Expression::Bool(x) => Value::Bool(x),
+        Expression::Number(x) => serde_json::to_value(x).unwrap_or_else(|_| Value::String(x.to_string())),
+        Expression::String(x) => Value::String(x),
+        Expression::Array(x) => Value::Array(x.into_iter().map(expression_to_json).collect()),
+        Expression::Object(x) => Value::Object(
+            x.into_iter()
+                .map(|(key, value)| (object_key(key), expression_to_json(value)))
+                .collect(),
+        ),
+        Expression::TemplateExpr(x) => match *x {
+            TemplateExpr::QuotedString(x) => Value::String(x),
+            TemplateExpr::Heredoc(x) => Value::String(x.template),
+        },
+        x => Value::String(x.to_string()),
+    }
+}
+
+fn object_key(key: ObjectKey) -> String {
+    match key {
+        ObjectKey::Identifier(x) => x.to_string(),
+        ObjectKey::Expression(Expression::String(x)) => x,
+        x => x.to_string(),
+    }
+}
+
+fn hcl_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "Hcl",
+        why: why.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use spectral::prelude::*;
+
+    #[test]
+    fn blocks_and_expressions() {
+        let hcl = r#"
+region = "eu-central-1"
+
+resource "aws_instance" "web" {
+  ami   = "ami-123"
+  count = 2
+  tags  = { Name = "${var.name}-web", "team" = "infra" }
+  subnet_id = aws_subnet.main.id
+
+  ebs_block_device {
+    device_name = "/dev/sdb"
+  }
+  ebs_block_device {
+    device_name = "/dev/sdc"
+  }
+}
+"#;
+
+        let res = hcl::parse(hcl)
+            .map_err(Error::from)
+            .and_then(body_to_json)
+            .map(Value::Object);
+
+        let expected = json!({
+            "region": "eu-central-1",
+            "resource": {
+                "aws_instance": {
+                    "web": {
+                        "ami": "ami-123",
+                        "count": 2,
+                        "tags": { "Name": "${var.name}-web", "team": "infra" },
+                        "subnet_id": "aws_subnet.main.id",
+                        "ebs_block_device": [
+                            { "device_name": "/dev/sdb" },
+                            { "device_name": "/dev/sdc" },
+                        ],
+                    }
+                }
+            }
+        });
+        asserting("hcl is converted successfully")
+            .that(&res)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+}
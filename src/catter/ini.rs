@@ -0,0 +1,258 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::io::Read;
+
+use serde_json::{Map, Value};
+
+use crate::catter::{Catter, JsonCatter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{Error, FlatCatOpts, Result};
+
+/// Flattens INI files like systemd units, `.desktop` files, and `.gitconfig`
+#[derive(Debug)]
+pub struct IniCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+}
+
+impl<'a> IniCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter) -> IniCatter<'b> {
+        IniCatter { opts, output }
+    }
+
+    fn ini(&mut self, ini: Map<String, Value>) -> Result<()> {
+        let mut path = KeyPath::new();
+
+        let mut catter = JsonCatter::new(self.opts, self.output);
+        catter.do_json(&mut path, Value::Object(ini))
+    }
+}
+
+impl<'a> Catter for IniCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        let mut buffer = String::new();
+        read.read_to_string(&mut buffer)?;
+        let ini = parse_ini(&buffer)?;
+
+        self.ini(ini)?;
+
+        Ok(())
+    }
+}
+
+/// Parses INI into nested objects of string values.
+///
+/// Headers like `[section]` and `[section "sub"]` become keys, keys before the first header are top-level,
+/// repeated keys like `ExecStartPre=` become arrays, and keys without `=` become null. Lines starting with `;`
+/// or `#` are comments; `;` and `#` after whitespace and outside of double quotes start a comment at the end of a
+/// line. A trailing backslash continues a line.
+fn parse_ini(ini: &str) -> Result<Map<String, Value>> {
+    let mut root = Map::new();
+    let mut section: Vec<String> = Vec::new();
+    let mut lines = ini.lines();
+
+    while let Some(line) = lines.next() {
+        let mut line = line.trim().to_string();
+        while line.ends_with('\\') {
+            line.pop();
+            line.truncate(line.trim_end().len());
+            line.push(' ');
+            match lines.next() {
+                Some(next) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+        let line = strip_comment(&line).trim();
+
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| ini_error(line, "unclosed section header"))?;
+            section = parse_header(header).ok_or_else(|| ini_error(line, "invalid section header"))?;
+            section_mut(&mut root, &section).ok_or_else(|| ini_error(line, "section conflicts with key"))?;
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), Value::String(unquote(value.trim()).to_string())),
+            None => (line, Value::Null),
+        };
+        let table = section_mut(&mut root, &section).ok_or_else(|| ini_error(line, "section conflicts with key"))?;
+        match table.get_mut(key) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                table.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    Ok(root)
+}
+
+/// Splits `section "sub"` into its section and quoted subsection; other headers like `Desktop Entry` are a single
+/// section
+fn parse_header(header: &str) -> Option<Vec<String>> {
+    let header = header.trim();
+    let subsection = header
+        .split_once(char::is_whitespace)
+        .and_then(|(name, sub)| Some((name, sub.trim().strip_prefix('"')?.strip_suffix('"')?)));
+    match subsection {
+        Some((name, sub)) => {
+            let sub = sub.replace("\\\"", "\"").replace("\\\\", "\\");
+            Some(vec![name.to_string(), sub])
+        }
+        None if !header.is_empty() => Some(vec![header.to_string()]),
+        None => None,
+    }
+}
+
+/// Returns the object of `section`, creating missing objects; returns `None` if a key of the path is a value
+fn section_mut<'a>(root: &'a mut Map<String, Value>, section: &[String]) -> Option<&'a mut Map<String, Value>> {
+    let mut table = root;
+    for name in section {
+        table = table
+            .entry(name.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()?;
+    }
+    Some(table)
+}
+
+/// Removes a comment like `; limit` at the end of a line; `;` and `#` within words like `#fff` or `a;b;` are kept
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut whitespace = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' | '#' if whitespace && !quoted => return &line[..i],
+            _ => {}
+        }
+        whitespace = c.is_whitespace();
+    }
+
+    line
+}
+
+/// Removes double quotes enclosing the whole value, but keeps quoted arguments like `"a" "b"`
+fn unquote(value: &str) -> &str {
+    match value.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+        Some(inner) if !inner.contains('"') => inner,
+        _ => value,
+    }
+}
+
+fn ini_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "Ini",
+        why: why.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use spectral::prelude::*;
+
+    #[test]
+    fn sections_and_repeated_keys() {
+        let ini = r#"
+; comment
+global = yes
+
+[Service]
+# comment
+ExecStartPre=/bin/mkdir -p /run/app
+ExecStartPre=/bin/chown app /run/app
+ExecStart=/usr/bin/app \
+  --verbose
+
+[remote "origin"]
+	url = "git@github.com:lukaspustina/flatcat.git"
+	mirror
+"#;
+
+        let res = parse_ini(ini).map(Value::Object);
+
+        let expected = json!({
+            "global": "yes",
+            "Service": {
+                "ExecStartPre": ["/bin/mkdir -p /run/app", "/bin/chown app /run/app"],
+                "ExecStart": "/usr/bin/app --verbose",
+            },
+            "remote": {
+                "origin": {
+                    "url": "git@github.com:lukaspustina/flatcat.git",
+                    "mirror": null,
+                }
+            }
+        });
+        asserting("ini is parsed successfully")
+            .that(&res)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn headers_with_whitespace() {
+        let ini = "[Desktop Entry]\nName=app\n[Desktop Action new-window]\nName=New Window\n";
+
+        let res = parse_ini(ini).map(Value::Object);
+
+        let expected = json!({
+            "Desktop Entry": { "Name": "app" },
+            "Desktop Action new-window": { "Name": "New Window" },
+        });
+        asserting("headers without quoted subsection are single sections")
+            .that(&res)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn inline_comments() {
+        let ini = r#"
+memory_limit = 128M ; Maximum amount of memory
+x = input # comment
+color=#fff
+Categories=a;b;
+name = "a ; b" ; c
+"#;
+
+        let res = parse_ini(ini).map(Value::Object);
+
+        let expected = json!({
+            "memory_limit": "128M",
+            "x": "input",
+            "color": "#fff",
+            "Categories": "a;b;",
+            "name": "a ; b",
+        });
+        asserting("inline comments are removed")
+            .that(&res)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn unclosed_header() {
+        let res = parse_ini("[Unit\nDescription=app");
+
+        asserting("unclosed header fails").that(&res.is_err()).is_true();
+    }
+}
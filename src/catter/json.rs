@@ -15,7 +15,6 @@ use crate::{FlatCatOpts, Result};
 
 #[derive(Debug)]
 pub struct JsonCatter<'a> {
-    #[allow(dead_code)]
     opts: &'a FlatCatOpts,
     output: &'a mut OutputWriter,
 }
@@ -31,27 +30,35 @@ impl<'a> JsonCatter<'a> {
         self.do_json(&mut path, json)
     }
 
-    fn do_json(&mut self, path: &mut KeyPath, json: Value) -> Result<()> {
+    pub(crate) fn do_json(&mut self, path: &mut KeyPath, json: Value) -> Result<()> {
         match json {
-            Value::Null => self.output.null(&path.to_string()),
-            Value::Bool(x) => self.output.bool(&path.path(), &x),
-            Value::Number(x) => self.output.number(&path.path(), &x),
-            Value::String(x) => self.output.string(&path.path(), &x),
-            Value::Array(x) => {
+            Value::Array(x) if !x.is_empty() => {
+                if self.opts.is_selected(path) {
+                    self.output.start_array(path);
+                }
                 for (i, value) in x.into_iter().enumerate() {
-                    let key = self.output.array(i);
-                    path.push_no_sep(&key);
+                    path.push_index(i);
                     self.do_json(path, value)?;
                     path.pop();
                 }
             }
-            Value::Object(x) => {
+            Value::Object(x) if !x.is_empty() => {
+                if self.opts.is_selected(path) {
+                    self.output.start_object(path);
+                }
                 for (key, value) in x {
                     path.push(&key);
                     self.do_json(path, value)?;
                     path.pop();
                 }
             }
+            _ if !self.opts.is_selected(path) => {}
+            Value::Array(_) => self.output.empty_array(path),
+            Value::Object(_) => self.output.empty_object(path),
+            Value::Null => self.output.null(path),
+            Value::Bool(x) => self.output.bool(path, x),
+            Value::Number(x) => self.output.number(path, x),
+            Value::String(x) => self.output.string(path, x),
         }
 
         Ok(())
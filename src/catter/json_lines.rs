@@ -0,0 +1,66 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::io::{BufRead, BufReader, Read};
+
+use serde_json::{de::from_str, Value};
+
+use crate::catter::{Catter, JsonCatter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{FlatCatOpts, Result};
+
+/// Flattens JSON Lines one record at a time, so endless streams like `tail -f` are supported; malformed records are
+/// skipped and passed to the skip handler of the options
+#[derive(Debug)]
+pub struct JsonLinesCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+}
+
+impl<'a> JsonLinesCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter) -> JsonLinesCatter<'b> {
+        JsonLinesCatter { opts, output }
+    }
+
+    fn json_lines<R: Read>(&mut self, read: R) -> Result<()> {
+        let buf_reader = BufReader::new(read);
+        let mut records = 0;
+        self.output.start_object(&KeyPath::new());
+        for line in buf_reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records += 1;
+            match from_str::<Value>(&line) {
+                Ok(json) => self.record(records, json)?,
+                Err(err) => {
+                    if let Some(skip_handler) = self.opts.skip_handler {
+                        skip_handler(records, &err.into());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record(&mut self, record: usize, json: Value) -> Result<()> {
+        let mut path = KeyPath::new();
+        path.push_no_sep(&format!("[record {}]", record));
+
+        let mut catter = JsonCatter::new(self.opts, self.output);
+        catter.do_json(&mut path, json)
+    }
+}
+
+impl<'a> Catter for JsonLinesCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        self.json_lines(read)?;
+        Ok(())
+    }
+}
@@ -7,71 +7,252 @@
 
 use std::io::Read;
 
+pub use crate::catter::csv::CsvCatter;
 pub use crate::catter::toml::TomlCatter;
+pub use dotenv::DotenvCatter;
+pub use hcl::HclCatter;
+pub use ini::IniCatter;
 pub use json::JsonCatter;
+pub use json_lines::JsonLinesCatter;
 pub use plain::PlainCatter;
+pub use properties::PropertiesCatter;
+pub use xml::XmlCatter;
 pub use yaml::YamlCatter;
 
-use crate::Result;
+use crate::{Error, Result};
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
+pub mod csv;
+pub mod dotenv;
+pub mod hcl;
+pub mod ini;
 pub mod json;
+pub mod json_lines;
 pub mod plain;
+pub mod properties;
 pub mod toml;
+pub mod xml;
 pub mod yaml;
 
-static KEY_SEPARATOR: char = '.';
+pub static KEY_SEPARATOR: &str = ".";
+
+/// Style to render a `KeyPath`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    /// `.persons[0].name`
+    Flat,
+    /// `/persons/0/name` according to RFC 6901
+    JsonPointer,
+}
+
+impl FromStr for PathStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(PathStyle::Flat),
+            "pointer" => Ok(PathStyle::JsonPointer),
+            _ => Err(Error::ParserError {
+                what: s.to_string(),
+                to: "PathStyle",
+                why: "unknown path style".to_string(),
+            }),
+        }
+    }
+}
 
 pub trait Catter {
     fn cat<R: Read>(&mut self, read: &mut R) -> Result<()>;
 }
 
-#[derive(Debug)]
-pub(crate) struct KeyPath {
-    path: String,
-    elements: Vec<usize>,
+/// A single element of a `KeyPath`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Key {
+    /// Key of a map, object, or table
+    Name(String),
+    /// Index of an array or sequence
+    Index(usize),
+    /// Verbatim path element which is not prefixed by the separator, e.g., a document prefix
+    Raw(String),
+}
+
+impl Key {
+    /// Names which would be ambiguous as part of a path are rendered as quoted and escaped JSON strings
+    pub fn needs_quotes(name: &str, separator: &str) -> bool {
+        name.is_empty()
+            || (!separator.is_empty() && name.contains(separator))
+            || name
+                .chars()
+                .any(|c| c == '[' || c == ']' || c == '"' || c == '\\' || c.is_whitespace() || c.is_control())
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Name(name) if Key::needs_quotes(name, KEY_SEPARATOR) => {
+                let quoted = serde_json::to_string(name).map_err(|_| fmt::Error)?;
+                f.write_fmt(format_args!("[{}]", quoted))
+            }
+            Key::Name(name) => f.write_fmt(format_args!("{}{}", KEY_SEPARATOR, name)),
+            Key::Index(i) => f.write_fmt(format_args!("[{}]", i)),
+            Key::Raw(raw) => f.write_str(raw),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyPath {
+    keys: Vec<Key>,
 }
 
 impl KeyPath {
     pub fn new() -> KeyPath {
-        KeyPath::new_with_capacity(1024)
+        KeyPath::new_with_capacity(32)
     }
 
     pub fn new_with_capacity(capacity: usize) -> KeyPath {
-        let path = String::with_capacity(capacity);
         KeyPath {
-            path,
-            elements: Vec::new(),
+            keys: Vec::with_capacity(capacity),
         }
     }
 
     pub fn push(&mut self, key: &str) {
-        self.path.push(KEY_SEPARATOR);
-        self.path.push_str(key);
-        self.elements.push(key.len() + 1)
+        self.keys.push(Key::Name(key.to_string()))
+    }
+
+    pub fn push_index(&mut self, index: usize) {
+        self.keys.push(Key::Index(index))
     }
 
     /// Push the key without prefixing with the separator
     pub fn push_no_sep(&mut self, key: &str) {
-        self.path.push_str(key);
-        self.elements.push(key.len())
+        self.keys.push(Key::Raw(key.to_string()))
     }
 
     pub fn pop(&mut self) {
-        if let Some(key_len) = self.elements.pop() {
-            self.path.truncate(self.path.len() - key_len);
+        let _ = self.keys.pop();
+    }
+
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Renders the path as JSON Pointer like `/persons/0/name`; numbered prefixes like `[doc 2]` become the 0-based
+    /// index of the document in the stream like `/1/persons`, other prefixes like `[Service/nginx]` become a key
+    pub fn json_pointer(&self) -> JsonPointer<'_> {
+        JsonPointer(self)
+    }
+
+    /// Parses the longest path prefix of `s` and returns the path and the remaining input.
+    ///
+    /// A key ends at the next separator, the next opening bracket, or the next `": "`; quoted keys like
+    /// `["app.kubernetes.io/name"]` are unescaped.
+    pub fn parse_prefix(s: &str) -> Result<(KeyPath, &str)> {
+        let mut path = KeyPath::new();
+        let mut rest = s;
+
+        loop {
+            if let Some(tail) = rest.strip_prefix(KEY_SEPARATOR) {
+                let end = tail
+                    .char_indices()
+                    .find(|&(i, c)| c == '[' || tail[i..].starts_with(KEY_SEPARATOR) || tail[i..].starts_with(": "))
+                    .map(|(i, _)| i)
+                    .unwrap_or_else(|| tail.len());
+                path.push(&tail[..end]);
+                rest = &tail[end..];
+            } else if rest.starts_with("[\"") {
+                let mut names = serde_json::Deserializer::from_str(&rest[1..]).into_iter::<String>();
+                let name = match names.next() {
+                    Some(Ok(name)) => name,
+                    _ => return Err(parser_error(s, "invalid quoted key")),
+                };
+                let end = names.byte_offset() + 1;
+                if !rest[end..].starts_with(']') {
+                    return Err(parser_error(s, "unclosed quoted key"));
+                }
+                path.push(&name);
+                rest = &rest[end + 1..];
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let end = tail.find(']').ok_or_else(|| parser_error(s, "unclosed bracket"))?;
+                match tail[..end].parse::<usize>() {
+                    Ok(index) => path.push_index(index),
+                    // Prefixes like `[doc 2]`
+                    Err(_) => path.push_no_sep(&rest[..end + 2]),
+                }
+                rest = &tail[end + 1..];
+            } else {
+                return Ok((path, rest));
+            }
         }
     }
+}
 
-    pub fn path(&self) -> &str {
-        self.path.as_str()
+impl Default for KeyPath {
+    fn default() -> Self {
+        KeyPath::new()
     }
 }
 
 impl Display for KeyPath {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.path)
+        for key in &self.keys {
+            key.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders a `KeyPath` as JSON Pointer according to RFC 6901
+pub struct JsonPointer<'a>(&'a KeyPath);
+
+impl<'a> Display for JsonPointer<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for key in self.0.keys() {
+            match key {
+                Key::Name(name) => f.write_fmt(format_args!("/{}", escape_reference_token(name)))?,
+                Key::Index(i) => f.write_fmt(format_args!("/{}", i))?,
+                Key::Raw(raw) => {
+                    let prefix = raw.trim_start_matches('[').trim_end_matches(']');
+                    match prefix.rsplit_once(' ').and_then(|(_, x)| x.parse::<usize>().ok()) {
+                        Some(number) => f.write_fmt(format_args!("/{}", number.saturating_sub(1)))?,
+                        None => f.write_fmt(format_args!("/{}", escape_reference_token(prefix)))?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn escape_reference_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+impl FromStr for KeyPath {
+    type Err = Error;
+
+    /// Parses a path as rendered by `Display`, e.g. `.persons[0].name`; non-numeric brackets are parsed as `Key::Raw`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (path, rest) = KeyPath::parse_prefix(s)?;
+        if !rest.is_empty() {
+            return Err(parser_error(s, "unexpected trailing characters"));
+        }
+        Ok(path)
+    }
+}
+
+fn parser_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "KeyPath",
+        why: why.to_string(),
     }
 }
 
@@ -113,6 +294,63 @@ mod tests {
                 .is_equal_to("[0]");
         }
 
+        #[test]
+        fn push_index() {
+            let mut path = KeyPath::new();
+
+            path.push("array");
+            path.push_index(0);
+
+            asserting("push 1 level and an index")
+                .that(&path.to_string().as_str())
+                .is_equal_to(".array[0]");
+        }
+
+        #[test]
+        fn push_ambiguous_keys() {
+            let mut path = KeyPath::new();
+
+            path.push("labels");
+            path.push("app.kubernetes.io/name");
+            path.push("[0]");
+            path.push("first name");
+
+            asserting("ambiguous keys are quoted")
+                .that(&path.to_string().as_str())
+                .is_equal_to(r#".labels["app.kubernetes.io/name"]["[0]"]["first name"]"#);
+        }
+
+        #[test]
+        fn json_pointer() {
+            let mut path = KeyPath::new();
+
+            path.push("persons");
+            path.push_index(0);
+            path.push("app.kubernetes.io/name");
+            path.push("a~b");
+
+            asserting("json pointer is escaped")
+                .that(&path.json_pointer().to_string().as_str())
+                .is_equal_to("/persons/0/app.kubernetes.io~1name/a~0b");
+        }
+
+        #[test]
+        fn json_pointer_with_prefix() {
+            let mut doc = KeyPath::new();
+            doc.push_no_sep("[doc 2]");
+            doc.push("kind");
+            let mut kind = KeyPath::new();
+            kind.push_no_sep("[Service/nginx]");
+            kind.push("kind");
+
+            asserting("numbered prefix is an index")
+                .that(&doc.json_pointer().to_string().as_str())
+                .is_equal_to("/1/kind");
+            asserting("other prefix is a key")
+                .that(&kind.json_pointer().to_string().as_str())
+                .is_equal_to("/Service~1nginx/kind");
+        }
+
         #[test]
         fn pop() {
             let mut path = KeyPath::new();
@@ -137,5 +375,53 @@ mod tests {
                 .that(&path.to_string().as_str())
                 .is_equal_to("");
         }
+
+        #[test]
+        fn from_str() {
+            let path = KeyPath::from_str(".persons[0].name.first_name").expect("failed to parse path");
+
+            let expected = vec![
+                Key::Name("persons".to_string()),
+                Key::Index(0),
+                Key::Name("name".to_string()),
+                Key::Name("first_name".to_string()),
+            ];
+            asserting("path is parsed successfully")
+                .that(&path.keys().to_vec())
+                .is_equal_to(expected);
+        }
+
+        #[test]
+        fn from_str_quoted() {
+            let path =
+                KeyPath::from_str(r#".labels["app.kubernetes.io/name"]["a \"b\""].c"#).expect("failed to parse path");
+
+            let expected = vec![
+                Key::Name("labels".to_string()),
+                Key::Name("app.kubernetes.io/name".to_string()),
+                Key::Name("a \"b\"".to_string()),
+                Key::Name("c".to_string()),
+            ];
+            asserting("quoted keys are parsed successfully")
+                .that(&path.keys().to_vec())
+                .is_equal_to(expected);
+        }
+
+        #[test]
+        fn from_str_raw() {
+            let path = KeyPath::from_str("[doc 2].spec").expect("failed to parse path");
+
+            let expected = vec![Key::Raw("[doc 2]".to_string()), Key::Name("spec".to_string())];
+            asserting("raw prefix is parsed successfully")
+                .that(&path.keys().to_vec())
+                .is_equal_to(expected);
+        }
+
+        #[test]
+        fn from_str_unclosed_bracket() {
+            let path = KeyPath::from_str(".persons[0");
+
+            asserting("unclosed bracket fails").that(&path).is_err();
+        }
     }
 }
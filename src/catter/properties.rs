@@ -0,0 +1,297 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::io::Read;
+
+use serde_json::{Map, Value};
+
+use crate::catter::{Catter, JsonCatter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{Error, FlatCatOpts, Result};
+
+/// Flattens Java properties like Spring's `application.properties`
+#[derive(Debug)]
+pub struct PropertiesCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+}
+
+impl<'a> PropertiesCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter) -> PropertiesCatter<'b> {
+        PropertiesCatter { opts, output }
+    }
+
+    fn properties(&mut self, properties: Map<String, Value>) -> Result<()> {
+        let mut path = KeyPath::new();
+
+        let mut catter = JsonCatter::new(self.opts, self.output);
+        catter.do_json(&mut path, Value::Object(properties))
+    }
+}
+
+impl<'a> Catter for PropertiesCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        let mut buffer = Vec::new();
+        read.read_to_end(&mut buffer)?;
+        let properties = parse_properties(&decode(buffer))?;
+
+        self.properties(properties)?;
+
+        Ok(())
+    }
+}
+
+/// Decodes UTF-8 like Spring does and falls back to ISO-8859-1, the encoding of `java.util.Properties`
+fn decode(buffer: Vec<u8>) -> String {
+    String::from_utf8(buffer).unwrap_or_else(|err| err.into_bytes().into_iter().map(char::from).collect())
+}
+
+/// Parses properties according to `java.util.Properties` into nested objects of string values.
+///
+/// Keys are split at `.`, so `spring.datasource.url` has the same path as in Spring's `application.yaml`. If a key
+/// is both a value and the parent of nested keys like `logging.level` and `logging.level.root`, the nested keys are
+/// kept as single keys, i.e., `.logging["level.root"]`, regardless of their order. Repeated keys overwrite previous
+/// values.
+fn parse_properties(properties: &str) -> Result<Map<String, Value>> {
+    let mut root = Map::new();
+
+    for line in logical_lines(properties) {
+        let (key, value) = split_key_value(&line);
+        let key = unescape(key).map_err(|why| properties_error(&line, why))?;
+        let value = unescape(value).map_err(|why| properties_error(&line, why))?;
+        insert(&mut root, &key, Value::String(value)).map_err(|why| properties_error(&line, why))?;
+    }
+
+    Ok(root)
+}
+
+/// Joins lines ending with an odd number of backslashes and skips blank lines and comments
+fn logical_lines(properties: &str) -> Vec<String> {
+    let mut logical_lines = Vec::new();
+    let mut lines = properties.lines();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        let mut logical_line = line.to_string();
+        while is_continued(&logical_line) {
+            logical_line.pop();
+            match lines.next() {
+                Some(next) => logical_line.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+        logical_lines.push(logical_line);
+    }
+
+    logical_lines
+}
+
+fn is_continued(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Splits at the first unescaped `=`, `:`, or whitespace; the value keeps its escape sequences
+fn split_key_value(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let end = line
+        .char_indices()
+        .find(|&(_, c)| {
+            let found = !escaped && (c == '=' || c == ':' || c.is_whitespace());
+            escaped = !escaped && c == '\\';
+            found
+        })
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| line.len());
+    let (key, rest) = line.split_at(end);
+
+    let rest = rest.trim_start();
+    let rest = rest
+        .strip_prefix('=')
+        .or_else(|| rest.strip_prefix(':'))
+        .unwrap_or(rest);
+    (key, rest.trim_start())
+}
+
+/// Decodes `\t`, `\n`, `\r`, `\f`, and `\uXXXX` including surrogate pairs; other escaped characters are kept
+fn unescape(s: &str) -> std::result::Result<String, &'static str> {
+    let mut res = String::with_capacity(s.len());
+    let mut units: Vec<u16> = Vec::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            flush_units(&mut res, &mut units)?;
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let unit = u16::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 4)
+                    .ok_or("invalid unicode escape")?;
+                units.push(unit);
+            }
+            Some(c) => {
+                flush_units(&mut res, &mut units)?;
+                res.push(match c {
+                    't' => '\t',
+                    'n' => '\n',
+                    'r' => '\r',
+                    'f' => '\x0c',
+                    c => c,
+                });
+            }
+            None => flush_units(&mut res, &mut units)?,
+        }
+    }
+    flush_units(&mut res, &mut units)?;
+
+    Ok(res)
+}
+
+fn flush_units(res: &mut String, units: &mut Vec<u16>) -> std::result::Result<(), &'static str> {
+    for c in char::decode_utf16(units.drain(..)) {
+        res.push(c.map_err(|_| "invalid surrogate pair")?);
+    }
+    Ok(())
+}
+
+fn insert(root: &mut Map<String, Value>, key: &str, value: Value) -> std::result::Result<(), &'static str> {
+    let mut table = root;
+    let mut rest = key;
+
+    while let Some((name, tail)) = rest.split_once('.') {
+        if table.get(name).is_some_and(|x| !x.is_object()) {
+            break;
+        }
+        table = table
+            .entry(name)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or("key conflicts with value")?;
+        rest = tail;
+    }
+    match table.get_mut(rest) {
+        Some(existing) if existing.is_object() => {
+            // Nested keys inserted before are moved next to the value as if they had been inserted after it
+            let nested = std::mem::replace(existing, value);
+            let mut keys = Vec::new();
+            flatten_keys(rest, nested, &mut keys);
+            for (key, value) in keys {
+                table.insert(key, value);
+            }
+        }
+        _ => {
+            table.insert(rest.to_string(), value);
+        }
+    }
+
+    Ok(())
+}
+
+fn flatten_keys(prefix: &str, value: Value, keys: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_keys(&format!("{}.{}", prefix, key), value, keys);
+            }
+        }
+        value => keys.push((prefix.to_string(), value)),
+    }
+}
+
+fn properties_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "Properties",
+        why: why.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use spectral::prelude::*;
+
+    #[test]
+    fn properties() {
+        let properties = r#"
+# comment
+! comment
+spring.datasource.url = jdbc:postgresql://localhost/app
+spring.datasource.username:app
+greeting=Gr\u00fc\u00dfe \ud83d\ude00\tand \
+    welcome
+path=C:\\temp
+logging.level=INFO
+logging.level.root=DEBUG
+key\ with\ spaces value
+"#;
+
+        let res = parse_properties(properties).map(Value::Object);
+
+        let expected = json!({
+            "spring": {
+                "datasource": {
+                    "url": "jdbc:postgresql://localhost/app",
+                    "username": "app",
+                }
+            },
+            "greeting": "Grüße 😀\tand welcome",
+            "path": "C:\\temp",
+            "logging": {
+                "level": "INFO",
+                "level.root": "DEBUG",
+            },
+            "key with spaces": "value",
+        });
+        asserting("properties are parsed successfully")
+            .that(&res)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn nested_keys_before_value() {
+        let res = parse_properties("logging.level.root=DEBUG\nlogging.level.web.sql=TRACE\nlogging.level=INFO\n");
+
+        let expected = json!({
+            "logging": {
+                "level": "INFO",
+                "level.root": "DEBUG",
+                "level.web.sql": "TRACE",
+            },
+        });
+        asserting("nested keys are kept as single keys")
+            .that(&res.map(Value::Object))
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn latin1() {
+        let properties = decode(b"greeting=Gr\xfc\xdfe\n".to_vec());
+
+        asserting("latin1 is decoded")
+            .that(&properties.as_str())
+            .is_equal_to("greeting=Grüße\n");
+    }
+
+    #[test]
+    fn invalid_unicode_escape() {
+        let res = parse_properties(r"key=\u00z1");
+
+        asserting("invalid unicode escape fails").that(&res.is_err()).is_true();
+    }
+}
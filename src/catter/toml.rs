@@ -15,7 +15,6 @@ use crate::{FlatCatOpts, Result};
 
 #[derive(Debug)]
 pub struct TomlCatter<'a> {
-    #[allow(dead_code)]
     opts: &'a FlatCatOpts,
     output: &'a mut OutputWriter,
 }
@@ -33,26 +32,34 @@ impl<'a> TomlCatter<'a> {
 
     fn do_toml(&mut self, path: &mut KeyPath, toml: Value) -> Result<()> {
         match toml {
-            Value::Boolean(x) => self.output.bool(&path.path(), &x),
-            Value::Integer(x) => self.output.number(&path.path(), &x),
-            Value::Float(x) => self.output.number(&path.path(), &x),
-            Value::String(x) => self.output.string(&path.path(), &x),
-            Value::Datetime(x) => self.output.datetime(&path.path(), &x),
-            Value::Array(x) => {
+            Value::Array(x) if !x.is_empty() => {
+                if self.opts.is_selected(path) {
+                    self.output.start_array(path);
+                }
                 for (i, value) in x.into_iter().enumerate() {
-                    let key = self.output.array(i);
-                    path.push_no_sep(&key);
+                    path.push_index(i);
                     self.do_toml(path, value)?;
                     path.pop();
                 }
             }
-            Value::Table(x) => {
+            Value::Table(x) if !x.is_empty() => {
+                if self.opts.is_selected(path) {
+                    self.output.start_object(path);
+                }
                 for (key, value) in x {
                     path.push(&key);
                     self.do_toml(path, value)?;
                     path.pop();
                 }
             }
+            _ if !self.opts.is_selected(path) => {}
+            Value::Array(_) => self.output.empty_array(path),
+            Value::Table(_) => self.output.empty_object(path),
+            Value::Boolean(x) => self.output.bool(path, x),
+            Value::Integer(x) => self.output.number(path, x),
+            Value::Float(x) => self.output.number(path, x),
+            Value::String(x) => self.output.string(path, x),
+            Value::Datetime(x) => self.output.datetime(path, x),
         }
 
         Ok(())
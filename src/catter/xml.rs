@@ -0,0 +1,367 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::catter::{Catter, KeyPath};
+use crate::output::OutputWriter;
+use crate::{FlatCatOpts, Result};
+
+/// Flattens XML like Maven POMs, Android manifests, or SOAP payloads.
+///
+/// Attributes are printed as `@name` and text of elements with attributes or child elements as `#text`. Repeated
+/// sibling elements are indexed like `.dependencies.dependency[3]`. Namespace prefixes are kept as written, e.g.,
+/// `.soap:Envelope.soap:Body`, and namespace declarations are printed as attributes like `@xmlns:soap`.
+///
+/// Indices and `#text` depend on later siblings, so the whole document is read and parsed before the first value is
+/// printed; malformed documents fail without partial output.
+#[derive(Debug)]
+pub struct XmlCatter<'a> {
+    opts: &'a FlatCatOpts,
+    output: &'a mut OutputWriter,
+}
+
+/// Structure of an element as collected by the first pass
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Element {
+    /// Element has siblings of the same name and is printed with an index
+    repeated: bool,
+    /// Element has attributes, child elements, or multiple texts and is printed as object
+    complex: bool,
+    /// Number of non-blank text and CDATA nodes
+    texts: usize,
+}
+
+/// Node of the document as collected by the first pass with names, attribute values, and texts decoded
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+        element: usize,
+    },
+    Text(String),
+    End,
+}
+
+/// Open element of the first pass and the names of its children
+type Scope = (Option<usize>, Vec<(String, usize)>);
+
+/// Element currently open during the second pass
+#[derive(Debug)]
+struct Open {
+    element: usize,
+    /// Next index of child elements per name
+    children: HashMap<String, usize>,
+    texts: usize,
+}
+
+impl<'a> XmlCatter<'a> {
+    pub fn new<'b>(opts: &'b FlatCatOpts, output: &'b mut OutputWriter) -> XmlCatter<'b> {
+        XmlCatter { opts, output }
+    }
+
+    /// Prints the elements in document order; the structure of the first pass decides about indices and `#text`
+    fn xml(&mut self, nodes: Vec<Node>, elements: &[Element]) {
+        let mut path = KeyPath::new();
+        let mut roots = HashMap::new();
+        let mut stack: Vec<Open> = Vec::new();
+
+        if self.opts.is_selected(&path) {
+            self.output.start_object(&path);
+        }
+        for node in nodes {
+            match node {
+                Node::Start {
+                    name,
+                    attributes,
+                    element,
+                } => {
+                    let children = stack.last_mut().map(|x| &mut x.children).unwrap_or(&mut roots);
+                    self.start(&mut path, name, attributes, &elements[element], children);
+                    stack.push(Open {
+                        element,
+                        children: HashMap::new(),
+                        texts: 0,
+                    });
+                }
+                Node::End => {
+                    if let Some(open) = stack.pop() {
+                        self.end(&mut path, &elements[open.element], open.texts);
+                    }
+                }
+                Node::Text(text) => self.text(&mut path, &mut stack, elements, text),
+            }
+        }
+    }
+
+    fn start(
+        &mut self,
+        path: &mut KeyPath,
+        name: String,
+        attributes: Vec<(String, String)>,
+        element: &Element,
+        children: &mut HashMap<String, usize>,
+    ) {
+        path.push(&name);
+        let index = children.entry(name).or_insert(0);
+        if element.repeated {
+            if *index == 0 && self.opts.is_selected(path) {
+                self.output.start_array(path);
+            }
+            path.push_index(*index);
+        }
+        *index += 1;
+
+        if !element.complex {
+            return;
+        }
+        if self.opts.is_selected(path) {
+            self.output.start_object(path);
+        }
+        for (key, value) in attributes {
+            path.push(&format!("@{}", key));
+            if self.opts.is_selected(path) {
+                self.output.string(path, value);
+            }
+            path.pop();
+        }
+    }
+
+    fn text(&mut self, path: &mut KeyPath, stack: &mut [Open], elements: &[Element], text: String) {
+        let open = match stack.last_mut() {
+            Some(open) => open,
+            None => return,
+        };
+        let element = &elements[open.element];
+        let index = open.texts;
+        open.texts += 1;
+
+        if !element.complex {
+            if self.opts.is_selected(path) {
+                self.output.string(path, text);
+            }
+            return;
+        }
+        path.push("#text");
+        if element.texts > 1 {
+            if index == 0 && self.opts.is_selected(path) {
+                self.output.start_array(path);
+            }
+            path.push_index(index);
+        }
+        if self.opts.is_selected(path) {
+            self.output.string(path, text);
+        }
+        if element.texts > 1 {
+            path.pop();
+        }
+        path.pop();
+    }
+
+    /// Prints elements without attributes, children, and text as null
+    fn end(&mut self, path: &mut KeyPath, element: &Element, texts: usize) {
+        if !element.complex && texts == 0 && self.opts.is_selected(path) {
+            self.output.null(path);
+        }
+        if element.repeated {
+            path.pop();
+        }
+        path.pop();
+    }
+}
+
+/// Collects the structure of all elements and the decoded nodes in document order without building a tree;
+/// unescaping is done here, so invalid entities fail before anything is printed
+fn scan(xml: &[u8]) -> Result<(Vec<Element>, Vec<Node>)> {
+    let mut reader = Reader::from_reader(xml);
+    reader.config_mut().trim_text(true);
+    let mut elements: Vec<Element> = Vec::new();
+    let mut nodes = Vec::new();
+    // The first scope collects the root elements
+    let mut stack: Vec<Scope> = vec![(None, Vec::new())];
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let element = add_element(&mut elements, &mut stack, &e);
+                nodes.push(start_node(&e, element)?);
+                stack.push((Some(element), Vec::new()));
+            }
+            Event::Empty(e) => {
+                let element = add_element(&mut elements, &mut stack, &e);
+                nodes.push(start_node(&e, element)?);
+                nodes.push(Node::End);
+            }
+            Event::End(_) if stack.len() > 1 => {
+                if let Some((element, children)) = stack.pop() {
+                    mark_repeated(&mut elements, &children);
+                    if let Some(element) = element {
+                        let element = &mut elements[element];
+                        element.complex |= element.texts > 1;
+                    }
+                }
+                nodes.push(Node::End);
+            }
+            Event::Text(e) if !e.is_empty() => {
+                let text = e.unescape()?.into_owned();
+                add_text(&mut elements, &mut nodes, &stack, text);
+            }
+            Event::CData(e) if !e.is_empty() => {
+                let text = e.decode().map_err(quick_xml::Error::from)?.into_owned();
+                add_text(&mut elements, &mut nodes, &stack, text);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    for (_, children) in &stack {
+        mark_repeated(&mut elements, children);
+    }
+
+    Ok((elements, nodes))
+}
+
+fn start_node(start: &BytesStart, element: usize) -> Result<Node> {
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        attributes.push((key, attribute.unescape_value()?.into_owned()));
+    }
+
+    Ok(Node::Start {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        attributes,
+        element,
+    })
+}
+
+fn add_element(elements: &mut Vec<Element>, stack: &mut [Scope], start: &BytesStart) -> usize {
+    let element = elements.len();
+    elements.push(Element {
+        complex: start.attributes().next().is_some(),
+        ..Element::default()
+    });
+    if let Some((parent, children)) = stack.last_mut() {
+        children.push((String::from_utf8_lossy(start.name().as_ref()).into_owned(), element));
+        if let Some(parent) = parent {
+            elements[*parent].complex = true;
+        }
+    }
+
+    element
+}
+
+/// Texts outside of the root element are ignored
+fn add_text(elements: &mut [Element], nodes: &mut Vec<Node>, stack: &[Scope], text: String) {
+    if let Some((Some(element), _)) = stack.last() {
+        elements[*element].texts += 1;
+        nodes.push(Node::Text(text));
+    }
+}
+
+fn mark_repeated(elements: &mut [Element], children: &[(String, usize)]) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (name, _) in children {
+        *counts.entry(name).or_insert(0) += 1;
+    }
+    for (name, element) in children {
+        elements[*element].repeated = counts[name.as_str()] > 1;
+    }
+}
+
+impl<'a> Catter for XmlCatter<'a> {
+    fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
+        let mut buffer = Vec::new();
+        read.read_to_end(&mut buffer)?;
+        let (elements, nodes) = scan(&buffer)?;
+
+        self.xml(nodes, &elements);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use spectral::prelude::*;
+
+    #[test]
+    fn scan_elements() {
+        let xml = br#"<project a="1"><dependency/><dependency><id>serde</id></dependency><name>fcat</name></project>"#;
+
+        let elements = scan(xml).map(|(elements, _)| elements);
+
+        let expected = vec![
+            Element {
+                repeated: false,
+                complex: true,
+                texts: 0,
+            },
+            Element {
+                repeated: true,
+                complex: false,
+                texts: 0,
+            },
+            Element {
+                repeated: true,
+                complex: true,
+                texts: 0,
+            },
+            Element {
+                repeated: false,
+                complex: false,
+                texts: 1,
+            },
+            Element {
+                repeated: false,
+                complex: false,
+                texts: 1,
+            },
+        ];
+        asserting("structure is collected")
+            .that(&elements)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn mixed_content() {
+        let elements = scan(b"<p>Hello <b>World</b> again</p>").map(|(elements, _)| elements);
+
+        let expected = vec![
+            Element {
+                repeated: false,
+                complex: true,
+                texts: 2,
+            },
+            Element {
+                repeated: false,
+                complex: false,
+                texts: 1,
+            },
+        ];
+        asserting("mixed content is complex")
+            .that(&elements)
+            .is_ok()
+            .is_equal_to(expected);
+    }
+
+    #[test]
+    fn invalid_entity() {
+        let res = scan(b"<project><name>fcat</name><url>&bogus;</url></project>");
+
+        asserting("invalid entity fails while scanning").that(&res).is_err();
+    }
+}
@@ -7,15 +7,15 @@
 
 use std::io::Read;
 
-use serde_yaml::{from_reader, Value};
+use serde::Deserialize;
+use serde_yaml::{Deserializer, Value};
 
 use crate::catter::{Catter, KeyPath};
 use crate::output::OutputWriter;
-use crate::{FlatCatOpts, Result};
+use crate::{DocPrefix, FlatCatOpts, Result};
 
 #[derive(Debug)]
 pub struct YamlCatter<'a> {
-    #[allow(dead_code)]
     opts: &'a FlatCatOpts,
     output: &'a mut OutputWriter,
 }
@@ -31,38 +31,139 @@ impl<'a> YamlCatter<'a> {
         self.do_yaml(&mut path, yaml)
     }
 
+    /// Flattens multiple documents each prefixed by its document prefix
+    fn yamls(&mut self, yamls: Vec<(usize, Value)>) -> Result<()> {
+        self.output.start_object(&KeyPath::new());
+        for (i, yaml) in yamls {
+            let mut path = KeyPath::new();
+            let prefix = self.doc_prefix(i, &yaml);
+            path.push_no_sep(&prefix);
+            self.do_yaml(&mut path, yaml)?;
+        }
+
+        Ok(())
+    }
+
+    fn doc_prefix(&self, index: usize, yaml: &Value) -> String {
+        if self.opts.doc_prefix == DocPrefix::KindName {
+            let kind = yaml.get("kind").and_then(Value::as_str);
+            let metadata = yaml.get("metadata");
+            let name = metadata.and_then(|x| x.get("name")).and_then(Value::as_str);
+            let namespace = metadata.and_then(|x| x.get("namespace")).and_then(Value::as_str);
+            match (kind, namespace, name) {
+                (Some(kind), Some(namespace), Some(name)) => return format!("[{}/{}/{}]", kind, namespace, name),
+                (Some(kind), None, Some(name)) => return format!("[{}/{}]", kind, name),
+                _ => {}
+            }
+        }
+
+        format!("[doc {}]", index + 1)
+    }
+
     fn do_yaml(&mut self, path: &mut KeyPath, yaml: Value) -> Result<()> {
         match yaml {
-            Value::Null => self.output.null(&path.path()),
-            Value::Bool(x) => self.output.bool(&path.path(), &x),
-            Value::Number(x) => self.output.number(&path.path(), &x),
-            Value::String(x) => self.output.string(&path.path(), &x),
-            Value::Sequence(x) => {
+            Value::Sequence(x) if !x.is_empty() => {
+                if self.opts.is_selected(path) {
+                    self.output.start_array(path);
+                }
                 for (i, value) in x.into_iter().enumerate() {
-                    let key = self.output.array(i);
-                    path.push_no_sep(&key);
+                    path.push_index(i);
                     self.do_yaml(path, value)?;
                     path.pop()
                 }
             }
-            Value::Mapping(x) => {
+            Value::Mapping(x) if !x.is_empty() => {
+                if self.opts.is_selected(path) {
+                    self.output.start_object(path);
+                }
                 for (key, value) in x {
-                    path.push(&key.as_str().unwrap());
+                    path.push(&yaml_key(&key));
                     self.do_yaml(path, value)?;
                     path.pop();
                 }
             }
+            _ if !self.opts.is_selected(path) => {}
+            Value::Sequence(_) => self.output.empty_array(path),
+            Value::Mapping(_) => self.output.empty_object(path),
+            Value::Null => self.output.null(path),
+            Value::Bool(x) => self.output.bool(path, x),
+            Value::Number(x) => self.output.number(path, x),
+            Value::String(x) => self.output.string(path, x),
         }
 
         Ok(())
     }
 }
 
+/// Renders non-string keys like numbers as their scalar value
+fn yaml_key(key: &Value) -> String {
+    match key {
+        Value::String(x) => x.to_string(),
+        Value::Number(x) => x.to_string(),
+        Value::Bool(x) => x.to_string(),
+        Value::Null => "null".to_string(),
+        x => serde_yaml::to_string(x)
+            .map(|x| x.trim_start_matches("---").trim().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Tells for each document of a stream whether it has any content besides comments; documents are started by `---`
+/// or the first content and ended by `...` or the next `---`
+fn document_contents(text: &str) -> Vec<bool> {
+    let is_marker = |line: &str, marker: &str| {
+        line.strip_prefix(marker)
+            .map(|x| x.is_empty() || x.starts_with(char::is_whitespace))
+            .unwrap_or(false)
+    };
+    let is_content = |line: &str| {
+        let line = line.trim();
+        !line.is_empty() && !line.starts_with('#')
+    };
+
+    let mut contents = Vec::new();
+    let mut document: Option<bool> = None;
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        if is_marker(line, "---") {
+            contents.extend(document);
+            document = Some(is_content(&line[3..]));
+        } else if is_marker(line, "...") {
+            contents.extend(document.take());
+        } else if document.is_none() {
+            // Directives like `%YAML 1.2` precede the document
+            if is_content(line) && !line.starts_with('%') {
+                document = Some(true);
+            }
+        } else if is_content(line) {
+            document = Some(true);
+        }
+    }
+    contents.extend(document);
+
+    contents
+}
+
 impl<'a> Catter for YamlCatter<'a> {
     fn cat<R: Read>(&mut self, read: &mut R) -> Result<()> {
-        let yaml: Value = from_reader(read)?;
+        let mut buffer = Vec::new();
+        read.read_to_end(buffer.as_mut())?;
+        let yamls = Deserializer::from_slice(&buffer)
+            .map(Value::deserialize)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        // Empty documents, e.g., caused by a trailing separator, are skipped but keep their position; explicit nulls
+        // are kept
+        let contents = document_contents(&String::from_utf8_lossy(&buffer));
+        let mut yamls: Vec<_> = yamls
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| contents.get(*i).copied().unwrap_or(true))
+            .collect();
 
-        self.yaml(yaml)?;
+        match yamls.len() {
+            0 => {}
+            1 => self.yaml(yamls.remove(0).1)?,
+            _ => self.yamls(yamls)?,
+        }
 
         Ok(())
     }
@@ -21,18 +21,84 @@ pub struct Opts {
     /// Files to flatten, print, and concat; if single '-' or absent, fcat reads from stdin
     #[structopt(name = "FILE")]
     pub files: Vec<String>,
-    /// Disables colorful output
+    /// Sets style of flattened lines; 'gron' prints JavaScript statements compatible with gron, 'env' environment variables, 'jsonl' JSON objects, 'csv' and 'tsv' tables with file, path, type, and value
+    #[structopt(long = "output", possible_values(& ["flat", "gron", "env", "jsonl", "csv", "tsv"]), default_value = "flat")]
+    pub output: String,
+    /// Prints environment variable assignments like 'PERSONS_0_NAME=Lukas'; same as '--output env'
+    #[structopt(long = "env")]
+    pub env: bool,
+    /// Prefixes environment variable assignments with 'export '
+    #[structopt(long = "export")]
+    pub export: bool,
+    /// Sets style of paths; 'pointer' prints JSON Pointers like '/persons/0/name'
+    #[structopt(long = "path-style", possible_values(& ["flat", "pointer"]), default_value = "flat")]
+    pub path_style: String,
+    /// Sets separator between keys of paths, e.g., '/', '__', or ':'
+    #[structopt(long = "separator", value_name = "SEP", default_value = ".")]
+    pub separator: String,
+    /// Sets syntax of array indices; 'separator' prints indices like keys, e.g., '.persons.0'
+    #[structopt(long = "index-style", possible_values(& ["brackets", "separator"]), default_value = "brackets")]
+    pub index_style: String,
+    /// Counts array indices starting at 1
+    #[structopt(long = "one-based")]
+    pub one_based: bool,
+    /// Sets prefix of all paths, e.g., '$'; an empty prefix drops the leading separator
+    #[structopt(long = "root", value_name = "PREFIX")]
+    pub root: Option<String>,
+    /// Omits the header of 'csv' and 'tsv' output to concatenate tables; see '--csv-no-header' for input files
+    #[structopt(long = "output-no-header")]
+    pub output_no_header: bool,
+    /// Terminates each line with NUL instead of newline, e.g., for 'xargs -0'
+    #[structopt(short = "z", long = "null-data")]
+    pub null_data: bool,
+    /// Sets delimiter between paths and values
+    #[structopt(long = "delimiter", value_name = "DELIM", default_value = ": ")]
+    pub delimiter: String,
+    /// Colors output; 'auto' colors if stdout is a terminal and respects NO_COLOR and CLICOLOR_FORCE
+    #[structopt(long = "color", value_name = "WHEN", possible_values(& ["auto", "always", "never"]), default_value = "auto")]
+    pub color: String,
+    /// Sets color theme; styles may be overridden by a config file and FCAT_COLORS, e.g., 'number=1;36:key=34'
+    #[structopt(long = "theme", value_name = "THEME", possible_values(& ["default", "dark", "light", "solarized"]))]
+    pub theme: Option<String>,
+    /// Colors keys alternating per depth level
+    #[structopt(long = "depth-colors")]
+    pub depth_colors: bool,
+    /// Disables colorful output; same as '--color never'
     #[structopt(long = "no-color")]
     pub no_color: bool,
+    /// Reads the first row of CSV and TSV files as data instead of column names; see '--output-no-header' for output
+    #[structopt(long = "csv-no-header")]
+    pub csv_no_header: bool,
+    /// Sets delimiter of CSV and TSV files, e.g., ';' or '|'
+    #[structopt(long = "csv-delimiter", value_name = "CHAR")]
+    pub csv_delimiter: Option<String>,
+    /// Infers numbers, booleans, and nulls from CSV and TSV values instead of reading all values as strings
+    #[structopt(long = "infer-types")]
+    pub infer_types: bool,
     /// Ignores null values, if applicable for file type
     #[structopt(long = "no-null")]
     pub no_null: bool,
+    /// Ignores empty arrays and objects
+    #[structopt(long = "no-empty")]
+    pub no_empty: bool,
     /// Flattens files with identified, hierarchically structured format
     #[structopt(short = "f", long = "flatten")]
     pub flatten: bool,
+    /// Sets prefix of paths for files with multiple documents
+    #[structopt(long = "doc-prefix", possible_values(& ["index", "kind"]), default_value = "index")]
+    pub doc_prefix: String,
+    /// Prints only paths matching pattern, e.g., 'spec.containers[*].image'; '*' matches one key, '**' any depth, '[*]' any index
+    #[structopt(long = "select", value_name = "PATTERN", number_of_values = 1)]
+    pub select: Vec<String>,
+    /// Omits paths matching pattern; same syntax as '--select'
+    #[structopt(long = "exclude", value_name = "PATTERN", number_of_values = 1)]
+    pub exclude: Vec<String>,
     /// Disables quoting ("text") strings
     #[structopt(long = "no-quotes")]
     pub no_quotes: bool,
+    /// Disables escaping newlines and control characters in strings
+    #[structopt(long = "no-escape")]
+    pub no_escape: bool,
     /// Numbers the output values, starting at 1
     #[structopt(short = "n", long = "numbers")]
     pub numbers: bool,
@@ -40,8 +106,17 @@ pub struct Opts {
     #[structopt(short = "e", long = "line-end")]
     pub end_of_line: bool,
     /// Sets file type instead of guessing
-    #[structopt(name = "TYPE", short = "t", long = "type", possible_values(& ["json", "toml", "yaml"]))]
+    #[structopt(name = "TYPE", short = "t", long = "type", possible_values(& ["csv", "dotenv", "hcl", "ini", "json", "jsonl", "properties", "toml", "tsv", "xml", "yaml"]))]
     pub format: Option<String>,
+    /// Compares the flattened paths and values of two files and exits with 1 if they differ and with 2 on errors
+    #[structopt(long = "diff")]
+    pub diff: bool,
+    /// Rebuilds a document from flattened lines as printed by fcat
+    #[structopt(long = "unflatten")]
+    pub unflatten: bool,
+    /// Sets file type of the rebuilt document when unflattening
+    #[structopt(name = "TO_TYPE", long = "to", possible_values(& ["json", "jsonl", "toml", "yaml"]), default_value = "json")]
+    pub to: String,
     /// Lists known file types / extensions for supported formats
     #[structopt(long = "type-list")]
     pub type_list: bool,
@@ -0,0 +1,153 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+
+use serde_json::Value;
+
+use crate::catter::KeyPath;
+use crate::output::OutputWriter;
+use crate::{FlatCat, FlatCatOpts, Input, Output, Result};
+
+/// Compares the flattened leaves of two documents by path and value
+///
+/// The documents may be of different formats, e.g., a JSON file and a YAML file, since only the leaves are compared.
+#[derive(Debug)]
+pub struct FlatDiff {
+    opts: FlatCatOpts,
+    output: OutputWriter,
+}
+
+impl FlatDiff {
+    pub fn new(opts: FlatCatOpts, output: Output) -> Result<FlatDiff> {
+        let opts = opts.with_flatten(true);
+        let output = output.try_into()?;
+        Ok(FlatDiff { opts, output })
+    }
+
+    /// Writes all removed, changed, and added paths and returns whether the documents differ
+    pub fn diff(&mut self, left: Input, right: Input) -> Result<bool> {
+        let left = self.leaves(left)?;
+        let right = self.leaves(right)?;
+        let right_values: HashMap<String, &Value> = right.iter().map(|(k, v)| (k.to_string(), v)).collect();
+        let left_paths: HashSet<String> = left.iter().map(|(k, _)| k.to_string()).collect();
+
+        let mut differences = false;
+        for (path, left_value) in &left {
+            match right_values.get(&path.to_string()) {
+                Some(right_value) if *right_value == left_value => {}
+                Some(right_value) => {
+                    self.output.changed(path, left_value, right_value);
+                    differences = true;
+                }
+                None => {
+                    self.output.removed(path, left_value);
+                    differences = true;
+                }
+            }
+        }
+        for (path, right_value) in &right {
+            if !left_paths.contains(&path.to_string()) {
+                self.output.added(path, right_value);
+                differences = true;
+            }
+        }
+
+        Ok(differences)
+    }
+
+    /// Flattens `input` and collects its paths and values; values are omitted like by the output, e.g., `--no-null`
+    fn leaves(&self, input: Input) -> Result<Vec<(KeyPath, Value)>> {
+        let mut flatcat = FlatCat {
+            opts: self.opts.clone(),
+            output: OutputWriter::leaf_collector(self.output.opts()),
+        };
+        flatcat.cat(input)?;
+
+        Ok(flatcat.output.into_leaves())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{FormatHint, OutputOpts};
+    use spectral::prelude::*;
+    use std::cell::RefCell;
+    use std::io::{self, Write};
+    use std::rc::Rc;
+
+    /// In-memory writer that stays accessible after being moved into an `Output`
+    #[derive(Debug, Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn diff(left: &'static str, right: &'static str) -> (bool, String) {
+        diff_with(OutputOpts::new(), left, right)
+    }
+
+    fn diff_with(opts: OutputOpts, left: &'static str, right: &'static str) -> (bool, String) {
+        let buffer = SharedBuffer::default();
+        let output = Output::from_writer(buffer.clone(), opts.with_color(false));
+        let mut flatdiff = FlatDiff::new(FlatCatOpts::new(), output).expect("failed to create diff");
+
+        let left = Input::from_reader(left.as_bytes()).with_format_hint(FormatHint::json());
+        let right = Input::from_reader(right.as_bytes()).with_format_hint(FormatHint::yaml());
+        let differences = flatdiff.diff(left, right).expect("failed to diff");
+        drop(flatdiff);
+
+        let lines = String::from_utf8(buffer.0.borrow().clone()).expect("invalid utf8");
+        (differences, lines)
+    }
+
+    #[test]
+    fn equal_documents_with_reordered_keys() {
+        let (differences, lines) = diff(r#"{ "a": 1, "b": [true, null] }"#, "b:\n  - true\n  -\na: 1\n");
+
+        asserting("no differences").that(&differences).is_false();
+        asserting("no output").that(&lines.as_str()).is_equal_to("");
+    }
+
+    #[test]
+    fn different_documents() {
+        let (differences, lines) = diff(r#"{ "a": 1, "b": "text", "c": true }"#, "a: 1\nb: other\nd: 42\n");
+
+        asserting("differences").that(&differences).is_true();
+        asserting("output")
+            .that(&lines.as_str())
+            .is_equal_to("~ .b: \"text\" -> \"other\"\n- .c: true\n+ .d: 42\n");
+    }
+
+    #[test]
+    fn type_change_is_a_difference() {
+        let (differences, lines) = diff(r#"{ "port": "8080" }"#, "port: 8080\n");
+
+        asserting("differences").that(&differences).is_true();
+        asserting("output")
+            .that(&lines.as_str())
+            .is_equal_to("~ .port: \"8080\" -> 8080\n");
+    }
+
+    #[test]
+    fn omitted_nulls_are_no_difference() {
+        let (differences, lines) = diff_with(OutputOpts::new().with_null(false), r#"{ "a": 1, "b": null }"#, "a: 1\n");
+
+        asserting("no differences").that(&differences).is_false();
+        asserting("no output").that(&lines.as_str()).is_equal_to("");
+    }
+}
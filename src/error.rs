@@ -22,11 +22,23 @@ pub enum Error {
     UnknownFormatError { msg: &'static str },
     #[error("failed to identify input format of file with extension '{ext}'")]
     UnknownFormatExtError { ext: String },
+    #[error("failed to identify input format of file with name '{name}'")]
+    UnknownFormatNameError { name: String },
     #[error("failed to execute IO operation for")]
     IoError {
         #[from]
         source: std::io::Error,
     },
+    #[error("failed to deserialize CSV")]
+    CsvError {
+        #[from]
+        source: csv::Error,
+    },
+    #[error("failed to deserialize HCL")]
+    HclError {
+        #[from]
+        source: hcl::Error,
+    },
     #[error("failed to deserialize to JSON")]
     JsonError {
         #[from]
@@ -37,6 +49,16 @@ pub enum Error {
         #[from]
         source: toml::de::Error,
     },
+    #[error("failed to serialize to Toml")]
+    TomlSerError {
+        #[from]
+        source: toml::ser::Error,
+    },
+    #[error("failed to deserialize XML")]
+    XmlError {
+        #[from]
+        source: quick_xml::Error,
+    },
     #[error("failed to deserialize to Yaml")]
     YamlError {
         #[from]
@@ -0,0 +1,567 @@
+/*
+ * Copyright 2021 Lukas Pustina <lukas@pustina.de>
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ *
+ */
+
+use serde_json::Value;
+
+use crate::{Error, Format, Result};
+
+/// Number of bytes at the start of an input used to guess its format
+pub const PEEK_LEN: usize = 4096;
+
+/// Guesses the format from the first bytes of a file's content
+pub struct FileContent {}
+
+impl FileContent {
+    pub fn guess_format(buf: &[u8]) -> Result<Format> {
+        // Shorter content is the complete input and must parse completely
+        let complete = buf.len() < PEEK_LEN;
+        let text = String::from_utf8_lossy(buf);
+        let text = text.trim_start_matches('\u{feff}');
+        let trimmed = text.trim_start();
+
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            if let Some(format) = guess_json(trimmed, complete) {
+                return Ok(format);
+            }
+        }
+        if trimmed.starts_with("<?xml") {
+            return Ok(Format::Xml);
+        }
+        if trimmed.starts_with("---") || trimmed.starts_with("%YAML") {
+            return Ok(Format::Yaml);
+        }
+
+        let lines = complete_lines(text);
+        if is_hcl(&lines) {
+            return Ok(Format::Hcl);
+        }
+        if is_toml(&lines) {
+            return Ok(Format::Toml);
+        }
+        if is_ini(&lines) {
+            return Ok(Format::Ini);
+        }
+        if is_dotenv(&lines) {
+            return Ok(Format::Dotenv);
+        }
+        if is_yaml(&lines) {
+            return Ok(Format::Yaml);
+        }
+        if is_csv(&lines, b',') {
+            return Ok(Format::Csv);
+        }
+        if is_csv(&lines, b'\t') {
+            return Ok(Format::Tsv);
+        }
+        if is_properties(&lines) {
+            return Ok(Format::Properties);
+        }
+
+        Err(Error::UnknownFormatError {
+            msg: "content does not match any supported format",
+        })
+    }
+}
+
+fn guess_json(text: &str, complete: bool) -> Option<Format> {
+    if serde_json::from_str::<Value>(text).is_ok() {
+        return Some(Format::Json);
+    }
+
+    let lines = complete_lines(text);
+    let mut records = lines.iter().filter(|x| !x.trim().is_empty());
+    let first = records.next()?;
+    let is_record = |line: &str| matches!(serde_json::from_str::<Value>(line), Ok(Value::Object(_)));
+    if is_record(first) && records.clone().next().is_some() && records.all(|x| is_record(x)) {
+        return Some(Format::JsonLines);
+    }
+
+    // A TOML table header also starts with a bracket
+    if complete || is_toml_table(first.trim()) {
+        return None;
+    }
+
+    // Assume a JSON document larger than the inspected content
+    Some(Format::Json)
+}
+
+/// Returns all lines except a last one which might have been cut off
+fn complete_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    if !text.ends_with('\n') && lines.len() > 1 {
+        lines.pop();
+    }
+    lines
+}
+
+fn is_comment_or_empty(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn is_key(key: &str) -> bool {
+    let key = key.trim();
+    let quoted = key.len() >= 2
+        && ((key.starts_with('"') && key.ends_with('"')) || (key.starts_with('\'') && key.ends_with('\'')));
+    quoted
+        || (!key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '/'))
+}
+
+fn is_toml_table(line: &str) -> bool {
+    let table = line
+        .strip_prefix("[[")
+        .and_then(|x| x.strip_suffix("]]"))
+        .or_else(|| line.strip_prefix('[').and_then(|x| x.strip_suffix(']')));
+    table.map(|x| x.split('.').all(is_key)).unwrap_or(false)
+}
+
+/// All lines are TOML tables or key value pairs; continuations of multi-line values are skipped
+fn is_toml(lines: &[&str]) -> bool {
+    let mut matches = 0;
+    let mut in_multi_line = false;
+
+    for line in lines.iter().filter(|x| !is_comment_or_empty(x)) {
+        if in_multi_line {
+            let trimmed = line.trim();
+            in_multi_line = !(trimmed.starts_with(']') || trimmed.ends_with("\"\"\"") || trimmed.ends_with("'''"));
+            continue;
+        }
+        if is_toml_table(line.trim()) {
+            matches += 1;
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, value)) if !line.starts_with(char::is_whitespace) && is_key(key) => {
+                let value = value.trim();
+                let opens_array = value.starts_with('[') && !value.ends_with(']');
+                let opens_string = ["\"\"\"", "'''"]
+                    .iter()
+                    .any(|q| value.starts_with(q) && (value.len() == 3 || !value[3..].ends_with(q)));
+                in_multi_line = opens_array || opens_string;
+                if !is_toml_value(value) {
+                    return false;
+                }
+                matches += 1;
+            }
+            _ => return false,
+        }
+    }
+
+    matches > 0
+}
+
+/// Values are quoted, arrays, inline tables, booleans, numbers, or dates; INI and dotenv values are unquoted text
+fn is_toml_value(value: &str) -> bool {
+    let value = value.split(" #").next().unwrap_or(value).trim();
+    if value.starts_with(['"', '\'', '[', '{']) || value == "true" || value == "false" {
+        return true;
+    }
+    let number = value.trim_start_matches(['+', '-']).replace('_', "");
+    ["inf", "nan"].contains(&number.as_str())
+        || number.parse::<f64>().is_ok()
+        || (number.starts_with("0x") || number.starts_with("0o") || number.starts_with("0b"))
+        || (value.len() >= 8 && value.starts_with(|c: char| c.is_ascii_digit()) && value.chars().all(is_date_char))
+}
+
+fn is_date_char(c: char) -> bool {
+    c.is_ascii_digit() || "-:.TZz+ ".contains(c)
+}
+
+/// All lines are section headers, `key=value` pairs, or `;` comments, and there is at least one section; lines
+/// continued by a trailing backslash are skipped
+fn is_ini(lines: &[&str]) -> bool {
+    let mut sections = 0;
+    let mut continued = false;
+
+    for line in lines.iter().map(|x| x.trim()) {
+        let is_continuation = continued;
+        continued = line.ends_with('\\');
+        if is_continuation || line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            sections += 1;
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, _)) if !key.trim().is_empty() && !key.contains('[') => {}
+            _ => return false,
+        }
+    }
+
+    sections > 0
+}
+
+/// All lines are shell-like `KEY=value` assignments optionally prefixed by `export`; quoted values may span lines
+fn is_dotenv(lines: &[&str]) -> bool {
+    let mut matches = 0;
+    let mut open_quote: Option<char> = None;
+
+    for line in lines {
+        if let Some(quote) = open_quote {
+            if line.contains(quote) {
+                open_quote = None;
+            }
+            continue;
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let assignment = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let (key, value) = match assignment.split_once('=') {
+            Some(x) => x,
+            None => return false,
+        };
+        let is_env_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_env_key {
+            return false;
+        }
+        open_quote = value
+            .chars()
+            .next()
+            .filter(|&q| q == '"' || q == '\'')
+            .filter(|&q| !value[1..].contains(q));
+        matches += 1;
+    }
+
+    matches > 0
+}
+
+/// All logical lines are `key=value` or `key:value` pairs with keys without whitespace; `!` also starts a comment
+fn is_properties(lines: &[&str]) -> bool {
+    let mut matches = 0;
+    let mut continued = false;
+
+    for line in lines {
+        let line = line.trim();
+        if continued {
+            continued = line.ends_with('\\');
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        let separator = line.find(['=', ':']);
+        match separator {
+            Some(i) if i > 0 && !line[..i].trim_end().contains(char::is_whitespace) => {}
+            _ => return false,
+        }
+        continued = line.ends_with('\\');
+        matches += 1;
+    }
+
+    matches > 0
+}
+
+/// A header and at least two records with the same number of at least two fields; header fields are distinct and not
+/// empty, and no field starts with whitespace like words after a comma in prose do
+fn is_csv(lines: &[&str], delimiter: u8) -> bool {
+    let content = lines.join("\n");
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut records = 0;
+    let mut fields = None;
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(x) => x,
+            Err(_) => return false,
+        };
+        if record.len() < 2 || fields.is_some_and(|x| x != record.len()) {
+            return false;
+        }
+        if record.iter().any(|x| x.starts_with(char::is_whitespace)) {
+            return false;
+        }
+        if records == 0 && !is_csv_header(&record) {
+            return false;
+        }
+        fields = Some(record.len());
+        records += 1;
+    }
+
+    records >= 3
+}
+
+fn is_csv_header(record: &csv::StringRecord) -> bool {
+    record
+        .iter()
+        .enumerate()
+        .all(|(i, x)| !x.is_empty() && !record.iter().take(i).any(|y| y == x))
+}
+
+/// Block headers like `resource "aws_instance" "web" {` are unique to HCL
+fn is_hcl(lines: &[&str]) -> bool {
+    lines
+        .iter()
+        .map(|x| x.trim())
+        .filter(|x| !x.starts_with('#') && !x.starts_with("//"))
+        .any(|line| {
+            let header = match line.strip_suffix('{') {
+                Some(x) if !x.contains('=') => x,
+                _ => return false,
+            };
+            let mut tokens = header.split_whitespace();
+            tokens.next().map(is_identifier).unwrap_or(false)
+                && tokens.all(|x| is_identifier(x) || (x.len() >= 2 && x.starts_with('"') && x.ends_with('"')))
+        })
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// All lines are YAML mapping entries or sequence items; content of block scalars is skipped
+fn is_yaml(lines: &[&str]) -> bool {
+    let mut matches = 0;
+    let mut block_indent: Option<usize> = None;
+
+    for line in lines.iter().filter(|x| !is_comment_or_empty(x)) {
+        // YAML forbids tabs for indentation
+        if line.starts_with('\t') {
+            return false;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if let Some(block) = block_indent {
+            if indent > block {
+                continue;
+            }
+            block_indent = None;
+        }
+
+        let mut item = line.trim();
+        while let Some(rest) = item.strip_prefix("- ") {
+            item = rest.trim_start();
+        }
+        if item == "-" {
+            matches += 1;
+            continue;
+        }
+        let (key, value) = match item.split_once(": ") {
+            Some(x) => x,
+            None => match item.strip_suffix(':') {
+                Some(key) => (key, ""),
+                None if item.len() < line.trim().len() => (item, ""),
+                None => return false,
+            },
+        };
+        if item.len() == line.trim().len() && !is_key(key) {
+            return false;
+        }
+        if value.starts_with('|') || value.starts_with('>') {
+            block_indent = Some(indent);
+        }
+        matches += 1;
+    }
+
+    matches > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn json() {
+        let content = b"{\n  \"persons\": [\n    { \"name\": \"Lukas\" }\n  ]\n}\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("json content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Json);
+    }
+
+    #[test]
+    fn truncated_json() {
+        let content = format!(
+            "[\n{}  {{ \"name\":",
+            "  { \"name\": \"Lukas\" },\n".repeat(PEEK_LEN / 10)
+        );
+
+        let format = FileContent::guess_format(&content.as_bytes()[..PEEK_LEN]);
+
+        asserting("truncated json content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Json);
+    }
+
+    #[test]
+    fn json_lines() {
+        let content = b"{\"level\":\"info\"}\n{\"level\":\"error\"}\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("json lines content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::JsonLines);
+    }
+
+    #[test]
+    fn toml() {
+        let content = b"# comment\nfile_type = \"toml\"\n\n[[persons]]\nage = 42\nlist = [\n  1,\n]\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("toml content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Toml);
+    }
+
+    #[test]
+    fn yaml() {
+        let content = b"persons:\n  - age: 42\n    script: |\n      echo hello\n      exit 0\n    name: Lukas\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("yaml content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Yaml);
+    }
+
+    #[test]
+    fn yaml_document_start() {
+        let content = b"---\nfile_type: yaml\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("yaml document start")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Yaml);
+    }
+
+    #[test]
+    fn xml_declaration() {
+        let content = b"<?xml version=\"1.0\"?>\n<project></project>\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("xml declaration")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Xml);
+    }
+
+    #[test]
+    fn plain_text() {
+        let content = b"# Text\n\nThis is a markdown file.\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("plain text content").that(&format).is_err();
+    }
+
+    #[test]
+    fn makefile() {
+        let content = b"all: check build\n\ncheck:\n\tcargo check\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("makefile content").that(&format).is_err();
+    }
+
+    #[test]
+    fn invalid_json() {
+        let content = b"[doc 1].a: 1\n[doc 2].b: 2\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("invalid json content").that(&format).is_err();
+    }
+
+    #[test]
+    fn ini() {
+        let content = b"[Unit]\nDescription=Foo bar\n\n[Service]\nExecStart=/usr/bin/foo \\\n    --bar\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("ini content").that(&format).is_ok().is_equal_to(Format::Ini);
+    }
+
+    #[test]
+    fn dotenv() {
+        let content =
+            b"# comment\nexport NODE_ENV=production\nCERT=\"-----BEGIN-----\nabc\n-----END-----\"\nPORT=3000\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("dotenv content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Dotenv);
+    }
+
+    #[test]
+    fn properties() {
+        let content =
+            b"! comment\nspring.datasource.url=jdbc:postgresql://localhost/app\ngreeting=Hello \\\n    World\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("properties content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Properties);
+    }
+
+    #[test]
+    fn csv() {
+        let content = b"name,age\nLukas,42\n\"Pustina, Lukas\",42\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("csv content").that(&format).is_ok().is_equal_to(Format::Csv);
+    }
+
+    #[test]
+    fn tsv() {
+        let content = b"name\tage\nLukas\t42\nAnna\t23\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("tsv content").that(&format).is_ok().is_equal_to(Format::Tsv);
+    }
+
+    #[test]
+    fn prose_with_commas() {
+        let content = b"Hello, world\nGoodbye, moon\nSee you, sun\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("prose content").that(&format).is_err();
+    }
+
+    #[test]
+    fn hcl() {
+        let content =
+            b"# comment\nregion = \"eu-central-1\"\n\nresource \"aws_instance\" \"web\" {\n  ami = \"ami-123\"\n}\n";
+
+        let format = FileContent::guess_format(content);
+
+        asserting("hcl content").that(&format).is_ok().is_equal_to(Format::Hcl);
+    }
+}
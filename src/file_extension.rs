@@ -12,7 +12,22 @@ use std::ffi::OsStr;
 
 use crate::{Error, Format, Result};
 
-pub static FILE_EXTENSION_LIST: &[(&str, &str)] = &[("json", "*.json"), ("toml", "*.toml"), ("yaml", "*.yaml, *.yml")];
+pub static FILE_EXTENSION_LIST: &[(&str, &str)] = &[
+    ("csv", "*.csv"),
+    ("dotenv", "*.env"),
+    ("hcl", "*.hcl, *.tf, *.tfvars"),
+    ("ini", "*.ini, *.desktop, *.service, *.socket, *.timer"),
+    ("json", "*.json"),
+    ("jsonl", "*.jsonl, *.ndjson"),
+    ("properties", "*.properties"),
+    ("toml", "*.toml"),
+    ("tsv", "*.tsv, *.tab"),
+    (
+        "xml",
+        "*.xml, *.xsd, *.xsl, *.xslt, *.wsdl, *.svg, *.csproj, *.fsproj, *.vbproj, *.resx",
+    ),
+    ("yaml", "*.yaml, *.yml"),
+];
 
 pub struct FileExtension {}
 
@@ -21,8 +36,19 @@ impl FileExtension {
         let str = ext.to_string_lossy();
         // see https://github.com/BurntSushi/ripgrep/blob/9c8d873a75ccb2a8d3ed692148becb2e72514732/crates/ignore/src/default_types.rs
         match str.as_ref() {
+            "csv" => Ok(Format::Csv),
+            "env" => Ok(Format::Dotenv),
+            "hcl" | "tf" | "tfvars" => Ok(Format::Hcl),
+            // Generic extensions like `.cfg` or `.path` are left to content detection
+            "ini" | "desktop" | "service" | "socket" | "timer" => Ok(Format::Ini),
             "json" => Ok(Format::Json),
+            "jsonl" | "ndjson" => Ok(Format::JsonLines),
+            "properties" => Ok(Format::Properties),
             "toml" => Ok(Format::Toml),
+            "tsv" | "tab" => Ok(Format::Tsv),
+            "xml" | "xsd" | "xsl" | "xslt" | "wsdl" | "svg" | "csproj" | "fsproj" | "vbproj" | "resx" => {
+                Ok(Format::Xml)
+            }
             "yaml" | "yml" => Ok(Format::Yaml),
             _ => Err(Error::UnknownFormatExtError { ext: str.into_owned() }),
         }
@@ -30,11 +56,69 @@ impl FileExtension {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests {
     use spectral::prelude::*;
 
     use super::*;
 
+    #[test]
+    fn tf() {
+        let ext = OsStr::new("tf");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("tf extension").that(&format).is_ok().is_equal_to(Format::Hcl);
+    }
+
+    #[test]
+    fn service() {
+        let ext = OsStr::new("service");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("service extension")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Ini);
+    }
+
+    #[test]
+    fn properties() {
+        let ext = OsStr::new("properties");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("properties extension")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Properties);
+    }
+
+    #[test]
+    fn tsv() {
+        let ext = OsStr::new("tsv");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("tsv extension")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Tsv);
+    }
+
+    #[test]
+    fn xml() {
+        let ext = OsStr::new("xml");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("xml extension")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Xml);
+    }
+
     #[test]
     fn json() {
         let ext = OsStr::new("json");
@@ -47,6 +131,30 @@ mod tests {
             .is_equal_to(&Format::Json);
     }
 
+    #[test]
+    fn jsonl() {
+        let ext = OsStr::new("jsonl");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("jsonl extension")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::JsonLines);
+    }
+
+    #[test]
+    fn ndjson() {
+        let ext = OsStr::new("ndjson");
+
+        let format = FileExtension::guess_format(ext);
+
+        asserting("ndjson extension")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::JsonLines);
+    }
+
     #[test]
     fn toml() {
         let ext = OsStr::new("toml");
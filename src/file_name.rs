@@ -0,0 +1,122 @@
+/*
+ * Copyright 2021 Lukas Pustina <lukas@pustina.de>
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ *
+ */
+
+use std::ffi::OsStr;
+
+use crate::{Error, Format, Result};
+
+pub static FILE_NAME_LIST: &[(&str, &str)] = &[
+    ("dotenv", ".env, .env.*"),
+    (
+        "ini",
+        ".editorconfig, .gitconfig, .gitmodules, .npmrc, .pypirc, pylintrc",
+    ),
+    (
+        "json",
+        ".babelrc, .eslintrc, .jshintrc, .watchmanconfig, composer.lock, flake.lock, Pipfile.lock",
+    ),
+    ("toml", "Cargo.lock, Gopkg.lock, Pipfile, poetry.lock, uv.lock"),
+    ("yaml", ".clang-format, .clang-tidy, .gemrc"),
+];
+
+/// Well-known files without or with an ambiguous file extension
+pub struct FileName {}
+
+impl FileName {
+    pub fn guess_format(name: &OsStr) -> Result<Format> {
+        let str = name.to_string_lossy();
+        match str.as_ref() {
+            ".env" => Ok(Format::Dotenv),
+            x if x.starts_with(".env.") => Ok(Format::Dotenv),
+            ".editorconfig" | ".gitconfig" | ".gitmodules" | ".npmrc" | ".pypirc" | "pylintrc" => Ok(Format::Ini),
+            ".babelrc" | ".eslintrc" | ".jshintrc" | ".watchmanconfig" | "composer.lock" | "flake.lock"
+            | "Pipfile.lock" => Ok(Format::Json),
+            "Cargo.lock" | "Gopkg.lock" | "Pipfile" | "poetry.lock" | "uv.lock" => Ok(Format::Toml),
+            ".clang-format" | ".clang-tidy" | ".gemrc" => Ok(Format::Yaml),
+            _ => Err(Error::UnknownFormatNameError { name: str.into_owned() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn env_local() {
+        let name = OsStr::new(".env.local");
+
+        let format = FileName::guess_format(name);
+
+        asserting(".env.local file name")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Dotenv);
+    }
+
+    #[test]
+    fn gitconfig() {
+        let name = OsStr::new(".gitconfig");
+
+        let format = FileName::guess_format(name);
+
+        asserting(".gitconfig file name")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Ini);
+    }
+
+    #[test]
+    fn pipfile_lock() {
+        let name = OsStr::new("Pipfile.lock");
+
+        let format = FileName::guess_format(name);
+
+        asserting("Pipfile.lock file name")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Json);
+    }
+
+    #[test]
+    fn babelrc() {
+        let name = OsStr::new(".babelrc");
+
+        let format = FileName::guess_format(name);
+
+        asserting(".babelrc file name")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Json);
+    }
+
+    #[test]
+    fn cargo_lock() {
+        let name = OsStr::new("Cargo.lock");
+
+        let format = FileName::guess_format(name);
+
+        asserting("Cargo.lock file name")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Toml);
+    }
+
+    #[test]
+    fn unknown() {
+        let name = OsStr::new("config");
+
+        let format = FileName::guess_format(name);
+
+        asserting("unknown file name").that(&format).is_err();
+    }
+}
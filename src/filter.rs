@@ -0,0 +1,297 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::str::FromStr;
+
+use crate::catter::{Key, KeyPath};
+use crate::Error;
+
+/// Selects and excludes paths by `PathPattern`s
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    select: Vec<PathPattern>,
+    exclude: Vec<PathPattern>,
+}
+
+impl PathFilter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_select(self, select: Vec<PathPattern>) -> Self {
+        PathFilter { select, ..self }
+    }
+
+    pub fn with_exclude(self, exclude: Vec<PathPattern>) -> Self {
+        PathFilter { exclude, ..self }
+    }
+
+    /// A path is selected if it matches any select pattern, if there are any, and no exclude pattern
+    pub fn is_selected(&self, path: &KeyPath) -> bool {
+        (self.select.is_empty() || self.select.iter().any(|x| x.is_match(path)))
+            && !self.exclude.iter().any(|x| x.is_match(path))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// Key name which may contain `*` and `?` wildcards
+    Name(String),
+    /// Quoted key name like `["app.kubernetes.io/name"]` which is matched exactly
+    Quoted(String),
+    Index(usize),
+    /// `[*]`
+    AnyIndex,
+    /// `**`
+    AnyDepth,
+}
+
+/// Pattern for paths like `spec.containers[*].image`
+///
+/// `*` matches one key, `**` matches any number of keys and indices, and `[*]` matches any index. Within
+/// key names, `*` and `?` may be used as wildcards for any number of characters and exactly one character,
+/// respectively. Quoted keys like `["app.kubernetes.io/name"]` are matched exactly. A pattern matches a path if
+/// it matches the path or any parent of the path so that the whole sub-tree is matched. Raw path elements like
+/// document prefixes are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathPattern {
+    segments: Vec<Segment>,
+}
+
+impl PathPattern {
+    pub fn is_match(&self, path: &KeyPath) -> bool {
+        let keys: Vec<&Key> = path.keys().iter().filter(|x| !matches!(x, Key::Raw(_))).collect();
+        matches_prefix(&self.segments, &keys)
+    }
+}
+
+fn matches_prefix(segments: &[Segment], keys: &[&Key]) -> bool {
+    let (segment, rest) = match segments.split_first() {
+        Some(x) => x,
+        None => return true,
+    };
+
+    match segment {
+        Segment::AnyDepth => (0..=keys.len()).any(|i| matches_prefix(rest, &keys[i..])),
+        _ => match keys.split_first() {
+            Some((key, keys)) if matches_key(segment, key) => matches_prefix(rest, keys),
+            _ => false,
+        },
+    }
+}
+
+fn matches_key(segment: &Segment, key: &Key) -> bool {
+    match (segment, key) {
+        (Segment::Name(pattern), Key::Name(name)) => matches_glob(pattern.as_bytes(), name.as_bytes()),
+        (Segment::Quoted(pattern), Key::Name(name)) => pattern == name,
+        (Segment::Index(i), Key::Index(j)) => i == j,
+        (Segment::AnyIndex, Key::Index(_)) => true,
+        _ => false,
+    }
+}
+
+fn matches_glob(pattern: &[u8], str: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => str.is_empty(),
+        Some((b'*', rest)) => (0..=str.len()).any(|i| matches_glob(rest, &str[i..])),
+        Some((b'?', rest)) => !str.is_empty() && matches_glob(rest, &str[1..]),
+        Some((c, rest)) => str.first() == Some(c) && matches_glob(rest, &str[1..]),
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = s.strip_prefix('.').unwrap_or(s);
+
+        while !rest.is_empty() {
+            if rest.starts_with("[\"") {
+                let mut names = serde_json::Deserializer::from_str(&rest[1..]).into_iter::<String>();
+                let name = match names.next() {
+                    Some(Ok(name)) => name,
+                    _ => return Err(parser_error(s, "invalid quoted key")),
+                };
+                let end = names.byte_offset() + 1;
+                if !rest[end..].starts_with(']') {
+                    return Err(parser_error(s, "unclosed quoted key"));
+                }
+                segments.push(Segment::Quoted(name));
+                rest = &rest[end + 1..];
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let end = tail.find(']').ok_or_else(|| parser_error(s, "unclosed array index"))?;
+                let segment = match &tail[..end] {
+                    "*" => Segment::AnyIndex,
+                    index => Segment::Index(index.parse().map_err(|_| parser_error(s, "invalid array index"))?),
+                };
+                segments.push(segment);
+                rest = &tail[end + 1..];
+            } else {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let segment = match &rest[..end] {
+                    "" => return Err(parser_error(s, "empty key")),
+                    "**" => Segment::AnyDepth,
+                    name => Segment::Name(name.to_string()),
+                };
+                segments.push(segment);
+                rest = &rest[end..];
+            }
+            rest = rest.strip_prefix('.').unwrap_or(rest);
+        }
+
+        Ok(PathPattern { segments })
+    }
+}
+
+fn parser_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "PathPattern",
+        why: why.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use spectral::prelude::*;
+
+    fn path(s: &str) -> KeyPath {
+        KeyPath::from_str(s).expect("failed to parse path")
+    }
+
+    fn pattern(s: &str) -> PathPattern {
+        PathPattern::from_str(s).expect("failed to parse pattern")
+    }
+
+    #[test]
+    fn exact_match() {
+        let pattern = pattern("persons[0].name");
+
+        asserting("exact path")
+            .that(&pattern.is_match(&path(".persons[0].name")))
+            .is_true();
+        asserting("other index")
+            .that(&pattern.is_match(&path(".persons[1].name")))
+            .is_false();
+    }
+
+    #[test]
+    fn sub_tree_match() {
+        let pattern = pattern(".persons");
+
+        asserting("child path")
+            .that(&pattern.is_match(&path(".persons[0].name")))
+            .is_true();
+        asserting("parent path").that(&pattern.is_match(&path(""))).is_false();
+    }
+
+    #[test]
+    fn wildcard_key() {
+        let pattern = pattern("spec.*.image");
+
+        asserting("one key")
+            .that(&pattern.is_match(&path(".spec.container.image")))
+            .is_true();
+        asserting("index is not a key")
+            .that(&pattern.is_match(&path(".spec[0].image")))
+            .is_false();
+        asserting("two keys")
+            .that(&pattern.is_match(&path(".spec.a.b.image")))
+            .is_false();
+    }
+
+    #[test]
+    fn glob_key() {
+        let pattern = pattern("meta*.na?e");
+
+        asserting("glob key")
+            .that(&pattern.is_match(&path(".metadata.name")))
+            .is_true();
+        asserting("non matching glob")
+            .that(&pattern.is_match(&path(".metadata.labels")))
+            .is_false();
+    }
+
+    #[test]
+    fn any_index() {
+        let pattern = pattern("spec.template.spec.containers[*].image");
+
+        asserting("any index")
+            .that(&pattern.is_match(&path(".spec.template.spec.containers[3].image")))
+            .is_true();
+    }
+
+    #[test]
+    fn quoted_key() {
+        let pattern = pattern(r#"metadata.labels["app.kubernetes.io/name"]"#);
+
+        asserting("quoted key")
+            .that(&pattern.is_match(&path(r#".metadata.labels["app.kubernetes.io/name"]"#)))
+            .is_true();
+        asserting("nested keys")
+            .that(&pattern.is_match(&path(".metadata.labels.app.kubernetes.io/name")))
+            .is_false();
+    }
+
+    #[test]
+    fn any_depth() {
+        let pattern = pattern("**.image");
+
+        asserting("deep path")
+            .that(&pattern.is_match(&path(".a[0].b.image")))
+            .is_true();
+        asserting("shallow path")
+            .that(&pattern.is_match(&path(".image")))
+            .is_true();
+        asserting("no match")
+            .that(&pattern.is_match(&path(".a.b.name")))
+            .is_false();
+    }
+
+    #[test]
+    fn raw_keys_are_ignored() {
+        let pattern = pattern("spec.replicas");
+        let mut path = KeyPath::new();
+        path.push_no_sep("[doc 2]");
+        path.push("spec");
+        path.push("replicas");
+
+        asserting("document prefix is ignored")
+            .that(&pattern.is_match(&path))
+            .is_true();
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        asserting("unclosed index")
+            .that(&PathPattern::from_str("persons[0"))
+            .is_err();
+        asserting("empty key")
+            .that(&PathPattern::from_str("persons..name"))
+            .is_err();
+    }
+
+    #[test]
+    fn filter() {
+        let filter = PathFilter::new()
+            .with_select(vec![pattern("persons")])
+            .with_exclude(vec![pattern("**.phone")]);
+
+        asserting("selected")
+            .that(&filter.is_selected(&path(".persons[0].age")))
+            .is_true();
+        asserting("excluded")
+            .that(&filter.is_selected(&path(".persons[0].phone")))
+            .is_false();
+        asserting("not selected")
+            .that(&filter.is_selected(&path(".file_type")))
+            .is_false();
+    }
+}
@@ -9,6 +9,7 @@
  */
 
 use crate::file_extension::FileExtension;
+use crate::file_name::FileName;
 use crate::{Error, Result};
 use std::fmt::{Display, Formatter};
 use std::path::Path;
@@ -21,14 +22,46 @@ pub enum FormatHint {
 }
 
 impl FormatHint {
+    pub fn csv() -> Self {
+        FormatHint::Hint(Format::Csv)
+    }
+
+    pub fn dotenv() -> Self {
+        FormatHint::Hint(Format::Dotenv)
+    }
+
+    pub fn hcl() -> Self {
+        FormatHint::Hint(Format::Hcl)
+    }
+
+    pub fn ini() -> Self {
+        FormatHint::Hint(Format::Ini)
+    }
+
     pub fn json() -> Self {
         FormatHint::Hint(Format::Json)
     }
 
+    pub fn json_lines() -> Self {
+        FormatHint::Hint(Format::JsonLines)
+    }
+
+    pub fn properties() -> Self {
+        FormatHint::Hint(Format::Properties)
+    }
+
     pub fn toml() -> Self {
         FormatHint::Hint(Format::Toml)
     }
 
+    pub fn tsv() -> Self {
+        FormatHint::Hint(Format::Tsv)
+    }
+
+    pub fn xml() -> Self {
+        FormatHint::Hint(Format::Xml)
+    }
+
     pub fn yaml() -> Self {
         FormatHint::Hint(Format::Yaml)
     }
@@ -40,12 +73,27 @@ impl FormatHint {
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Format {
+    Csv,
+    Dotenv,
+    Hcl,
+    Ini,
     Json,
+    JsonLines,
+    Properties,
     Toml,
+    Tsv,
+    Xml,
     Yaml,
 }
 
 impl Format {
+    /// Guesses the format from well-known file names first and from the file extension second
+    pub fn guess_from_file_name<P: AsRef<Path>>(path: P) -> Result<Format> {
+        let p = path.as_ref();
+        let name = p.file_name().ok_or(Error::UnknownFormatError { msg: "not a file" })?;
+        FileName::guess_format(name).or_else(|_| Format::guess_from_file_extension(p))
+    }
+
     pub fn guess_from_file_extension<P: AsRef<Path>>(path: P) -> Result<Format> {
         let p = path.as_ref();
         let _ = p.file_name().ok_or(Error::UnknownFormatError { msg: "not a file" })?;
@@ -64,8 +112,16 @@ impl FromStr for Format {
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
+            "csv" => Ok(Format::Csv),
+            "dotenv" => Ok(Format::Dotenv),
+            "hcl" => Ok(Format::Hcl),
+            "ini" => Ok(Format::Ini),
             "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::JsonLines),
+            "properties" => Ok(Format::Properties),
             "toml" => Ok(Format::Toml),
+            "tsv" => Ok(Format::Tsv),
+            "xml" => Ok(Format::Xml),
             "yaml" => Ok(Format::Yaml),
             _ => Err(Error::ParserError {
                 what: s.to_string(),
@@ -79,8 +135,16 @@ impl FromStr for Format {
 impl Display for Format {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let str = match self {
+            Format::Csv => "csv",
+            Format::Dotenv => "dotenv",
+            Format::Hcl => "hcl",
+            Format::Ini => "ini",
             Format::Json => "json",
+            Format::JsonLines => "jsonl",
+            Format::Properties => "properties",
             Format::Toml => "toml",
+            Format::Tsv => "tsv",
+            Format::Xml => "xml",
             Format::Yaml => "yaml",
         };
         f.write_str(str)
@@ -88,11 +152,52 @@ impl Display for Format {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests {
     use super::*;
 
     use spectral::prelude::*;
 
+    #[test]
+    fn csv_from_str() {
+        let format = Format::from_str(&Format::Csv.to_string());
+
+        asserting("csv is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Csv);
+    }
+
+    #[test]
+    fn dotenv_from_str() {
+        let format = Format::from_str(&Format::Dotenv.to_string());
+
+        asserting("dotenv is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Dotenv);
+    }
+
+    #[test]
+    fn hcl_from_str() {
+        let format = Format::from_str(&Format::Hcl.to_string());
+
+        asserting("hcl is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Hcl);
+    }
+
+    #[test]
+    fn ini_from_str() {
+        let format = Format::from_str(&Format::Ini.to_string());
+
+        asserting("ini is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Ini);
+    }
+
     #[test]
     fn json_from_str() {
         let format = Format::from_str(&Format::Json.to_string());
@@ -103,6 +208,26 @@ mod tests {
             .is_equal_to(&Format::Json);
     }
 
+    #[test]
+    fn json_lines_from_str() {
+        let format = Format::from_str(&Format::JsonLines.to_string());
+
+        asserting("jsonl is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::JsonLines);
+    }
+
+    #[test]
+    fn properties_from_str() {
+        let format = Format::from_str(&Format::Properties.to_string());
+
+        asserting("properties is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Properties);
+    }
+
     #[test]
     fn toml_from_str() {
         let format = Format::from_str(&Format::Toml.to_string());
@@ -113,6 +238,16 @@ mod tests {
             .is_equal_to(&Format::Toml);
     }
 
+    #[test]
+    fn xml_from_str() {
+        let format = Format::from_str(&Format::Xml.to_string());
+
+        asserting("xml is parsed successfully")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Xml);
+    }
+
     #[test]
     fn yaml_from_str() {
         let format = Format::from_str(&Format::Yaml.to_string());
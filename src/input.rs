@@ -15,6 +15,7 @@ use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::{fmt, io};
 
+use crate::file_content::{FileContent, PEEK_LEN};
 use crate::{Error, Format, FormatHint, Result};
 
 pub enum Input {
@@ -45,10 +46,19 @@ impl Input {
         }
     }
 
+    /// Name of the input for output; `-` for stdin
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Input::Path(path, _) => Some(path.to_string_lossy().into_owned()),
+            Input::Read(_, _) => None,
+            Input::StdIn(_) => Some("-".to_string()),
+        }
+    }
+
     pub fn format(&self) -> Result<Format> {
         match self {
             Input::Path(_, FormatHint::Hint(format)) => Ok(*format),
-            Input::Path(p, FormatHint::Empty) => Format::guess_from_file_extension(p),
+            Input::Path(p, FormatHint::Empty) => Format::guess_from_file_name(p),
             Input::Read(_, FormatHint::Hint(format)) => Ok(*format),
             Input::Read(_, FormatHint::Empty) => Err(Error::UnknownFormatError {
                 msg: "cannot determine format from stream",
@@ -73,10 +83,51 @@ impl Debug for Input {
 
 pub struct InputReader {
     inner: Box<dyn Read>,
+    /// Bytes already read from `inner` by `peek`, but not yet consumed
+    peeked: Vec<u8>,
+    pos: usize,
+}
+
+impl InputReader {
+    fn new(inner: Box<dyn Read>) -> Self {
+        InputReader {
+            inner,
+            peeked: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Returns up to `len` bytes from the start of the input without consuming them
+    ///
+    /// Blocks until `len` bytes are available or the input ends.
+    pub fn peek(&mut self, len: usize) -> io::Result<&[u8]> {
+        let mut buf = [0u8; 1024];
+        while self.peeked.len() < len {
+            let n = self.inner.read(&mut buf[..(len - self.peeked.len()).min(1024)])?;
+            if n == 0 {
+                break;
+            }
+            self.peeked.extend_from_slice(&buf[..n]);
+        }
+
+        Ok(&self.peeked[self.pos..])
+    }
+
+    /// Guesses the format from the start of the content
+    pub fn guess_format(&mut self) -> Result<Format> {
+        let buf = self.peek(PEEK_LEN)?;
+        FileContent::guess_format(buf)
+    }
 }
 
 impl Read for InputReader {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos < self.peeked.len() {
+            let n = (self.peeked.len() - self.pos).min(buf.len());
+            buf[..n].copy_from_slice(&self.peeked[self.pos..self.pos + n]);
+            self.pos += n;
+            return Ok(n);
+        }
         self.inner.read(buf)
     }
 }
@@ -89,18 +140,52 @@ impl TryFrom<Input> for InputReader {
             Input::Path(p, _) => {
                 let file = File::open(p)?;
                 let buf_reader = BufReader::new(file);
-                Ok(InputReader {
-                    inner: Box::new(buf_reader),
-                })
+                Ok(InputReader::new(Box::new(buf_reader)))
             }
-            Input::Read(inner, _) => Ok(InputReader { inner }),
+            Input::Read(inner, _) => Ok(InputReader::new(inner)),
             Input::StdIn(_) => {
                 let stdin = io::stdin();
                 let buf_reader = BufReader::new(stdin);
-                Ok(InputReader {
-                    inner: Box::new(buf_reader),
-                })
+                Ok(InputReader::new(Box::new(buf_reader)))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use spectral::prelude::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn peek_does_not_consume() {
+        let input = Input::from_reader("file_type: yaml".as_bytes());
+        let mut reader: InputReader = input.try_into().expect("failed to create reader");
+
+        let peeked = reader.peek(4).expect("failed to peek").to_vec();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).expect("failed to read");
+
+        asserting("peeked bytes")
+            .that(&peeked.as_slice())
+            .is_equal_to(&b"file"[..]);
+        asserting("content is complete")
+            .that(&content.as_str())
+            .is_equal_to("file_type: yaml");
+    }
+
+    #[test]
+    fn guess_format_from_stream() {
+        let input = Input::from_reader("{ \"file_type\": \"json\" }".as_bytes());
+        let mut reader: InputReader = input.try_into().expect("failed to create reader");
+
+        let format = reader.guess_format();
+
+        asserting("json content")
+            .that(&format)
+            .is_ok()
+            .is_equal_to(Format::Json);
+    }
+}
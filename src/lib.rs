@@ -7,28 +7,56 @@
 
 pub use error::Error;
 
+pub use crate::catter::PathStyle;
+pub use crate::diff::FlatDiff;
+pub use crate::filter::{PathFilter, PathPattern};
 pub use crate::format::{Format, FormatHint};
 pub use crate::input::Input;
 use crate::input::InputReader;
 use crate::output::OutputWriter;
-pub use crate::output::{Output, OutputOpts};
+pub use crate::output::{ColorChoice, IndexStyle, Output, OutputOpts, OutputStyle};
+pub use crate::theme::Theme;
+pub use crate::unflatten::UnflatCat;
 
 use std::convert::TryInto;
+use std::io::Read;
+use std::str::FromStr;
 
 pub mod catter;
 pub mod cli_parser;
+pub mod diff;
 pub mod error;
+pub mod file_content;
 pub mod file_extension;
+pub mod file_name;
+pub mod filter;
 pub mod format;
 pub mod input;
 pub mod output;
+pub mod theme;
+pub mod unflatten;
 
 pub type Result<T> = std::result::Result<T, error::Error>;
 
+/// Receives the number and parse error of each malformed record that is skipped, e.g., of JSON Lines
+pub type SkipHandler = fn(usize, &Error);
+
 #[derive(Debug, Clone)]
 pub struct FlatCatOpts {
     /// If set, the flatten files with identified, hierarchically structured content
     flatten: bool,
+    /// Prefix for paths of files containing multiple documents
+    doc_prefix: DocPrefix,
+    /// Selects the paths to print
+    filter: PathFilter,
+    /// If set, the first row of CSV and TSV files contains the column names
+    csv_header: bool,
+    /// Delimiter of CSV and TSV files instead of `,` and tab, respectively
+    csv_delimiter: Option<u8>,
+    /// If set, numbers, booleans, and empty values of CSV and TSV files are printed as such instead of as strings
+    infer_types: bool,
+    /// If set, is called for malformed records that are skipped
+    skip_handler: Option<SkipHandler>,
 }
 
 impl FlatCatOpts {
@@ -37,13 +65,78 @@ impl FlatCatOpts {
     }
 
     pub fn with_flatten(self, flatten: bool) -> Self {
-        FlatCatOpts { flatten }
+        FlatCatOpts { flatten, ..self }
+    }
+
+    pub fn with_doc_prefix(self, doc_prefix: DocPrefix) -> Self {
+        FlatCatOpts { doc_prefix, ..self }
+    }
+
+    pub fn with_filter(self, filter: PathFilter) -> Self {
+        FlatCatOpts { filter, ..self }
+    }
+
+    pub fn with_csv_header(self, csv_header: bool) -> Self {
+        FlatCatOpts { csv_header, ..self }
+    }
+
+    pub fn with_csv_delimiter(self, csv_delimiter: Option<u8>) -> Self {
+        FlatCatOpts { csv_delimiter, ..self }
+    }
+
+    pub fn with_infer_types(self, infer_types: bool) -> Self {
+        FlatCatOpts { infer_types, ..self }
+    }
+
+    pub fn with_skip_handler(self, skip_handler: SkipHandler) -> Self {
+        FlatCatOpts {
+            skip_handler: Some(skip_handler),
+            ..self
+        }
+    }
+
+    pub fn is_selected(&self, path: &catter::KeyPath) -> bool {
+        self.filter.is_selected(path)
     }
 }
 
 impl Default for FlatCatOpts {
     fn default() -> Self {
-        FlatCatOpts { flatten: true }
+        FlatCatOpts {
+            flatten: true,
+            doc_prefix: DocPrefix::Index,
+            filter: PathFilter::new(),
+            csv_header: true,
+            csv_delimiter: None,
+            infer_types: false,
+            skip_handler: None,
+        }
+    }
+}
+
+/// Identifies the document of a path in files containing multiple documents
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DocPrefix {
+    /// Position of the document starting at 1, e.g., `[doc 2]`
+    Index,
+    /// Kubernetes-style `kind`, `metadata.namespace` if present, and `metadata.name` of the document, e.g.,
+    /// `[Deployment/nginx]` or `[Deployment/web/nginx]`; falls back to `Index`
+    KindName,
+}
+
+impl FromStr for DocPrefix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "index" => Ok(DocPrefix::Index),
+            "kind" => Ok(DocPrefix::KindName),
+            _ => Err(Error::ParserError {
+                what: s.to_string(),
+                to: "DocPrefix",
+                why: "unknown document prefix".to_string(),
+            }),
+        }
     }
 }
 
@@ -60,27 +153,85 @@ impl FlatCat {
     }
 
     pub fn cat(&mut self, input: Input) -> Result<()> {
-        use crate::catter::Catter;
-
         let format = input.format();
+        self.output.set_file(input.name());
         let mut reader: InputReader = input.try_into()?;
+        let guessed = format.is_err();
+        let format = match format {
+            Err(_) if self.opts.flatten => reader.guess_format(),
+            x => x,
+        };
+
+        match format {
+            // Formats guessed from content may be wrong, so content that fails to parse is printed plainly; JSON Lines,
+            // CSV, and TSV are streamed and not buffered
+            Ok(format) if guessed && !matches!(format, Format::Csv | Format::JsonLines | Format::Tsv) => {
+                let mut buffer = Vec::new();
+                reader.read_to_end(&mut buffer)?;
+                self.cat_format(Ok(format), &mut buffer.as_slice()).or_else(|_| {
+                    self.cat_format(
+                        Err(Error::UnknownFormatError {
+                            msg: "content does not match guessed format",
+                        }),
+                        &mut buffer.as_slice(),
+                    )
+                })
+            }
+            format => self.cat_format(format, &mut reader),
+        }
+    }
+
+    fn cat_format<R: Read>(&mut self, format: Result<Format>, reader: &mut R) -> Result<()> {
+        use crate::catter::Catter;
 
         match format {
+            Ok(Format::Csv) if self.opts.flatten => {
+                let mut catter = catter::CsvCatter::new(&self.opts, &mut self.output, b',');
+                catter.cat(reader)
+            }
+            Ok(Format::Dotenv) if self.opts.flatten => {
+                let mut catter = catter::DotenvCatter::new(&self.opts, &mut self.output);
+                catter.cat(reader)
+            }
+            Ok(Format::Hcl) if self.opts.flatten => {
+                let mut catter = catter::HclCatter::new(&self.opts, &mut self.output);
+                catter.cat(reader)
+            }
+            Ok(Format::Ini) if self.opts.flatten => {
+                let mut catter = catter::IniCatter::new(&self.opts, &mut self.output);
+                catter.cat(reader)
+            }
             Ok(Format::Json) if self.opts.flatten => {
                 let mut catter = catter::JsonCatter::new(&self.opts, &mut self.output);
-                catter.cat(&mut reader)
+                catter.cat(reader)
+            }
+            Ok(Format::JsonLines) if self.opts.flatten => {
+                let mut catter = catter::JsonLinesCatter::new(&self.opts, &mut self.output);
+                catter.cat(reader)
+            }
+            Ok(Format::Properties) if self.opts.flatten => {
+                let mut catter = catter::PropertiesCatter::new(&self.opts, &mut self.output);
+                catter.cat(reader)
             }
             Ok(Format::Toml) if self.opts.flatten => {
                 let mut catter = catter::TomlCatter::new(&self.opts, &mut self.output);
-                catter.cat(&mut reader)
+                catter.cat(reader)
+            }
+            Ok(Format::Tsv) if self.opts.flatten => {
+                let mut catter = catter::CsvCatter::new(&self.opts, &mut self.output, b'\t');
+                catter.cat(reader)
+            }
+            Ok(Format::Xml) if self.opts.flatten => {
+                let mut catter = catter::XmlCatter::new(&self.opts, &mut self.output);
+                catter.cat(reader)
             }
             Ok(Format::Yaml) if self.opts.flatten => {
                 let mut catter = catter::YamlCatter::new(&self.opts, &mut self.output);
-                catter.cat(&mut reader)
+                catter.cat(reader)
             }
             Ok(_) | Err(_) => {
                 let mut catter = catter::PlainCatter::new(&self.opts, &mut self.output);
-                catter.cat(&mut reader)
+                catter.cat(reader)
             }
         }
     }
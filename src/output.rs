@@ -5,31 +5,152 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use lazy_static::lazy_static;
+use crate::catter::{Key, KeyPath, PathStyle, KEY_SEPARATOR};
+use crate::theme::Theme;
+use serde_json::{Map, Value};
 use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::str::FromStr;
 use std::{fmt, io};
-use yansi::{Color, Style};
+use yansi::Style;
 
 static EMPTY_STR: &str = "";
-lazy_static! {
-    static ref STYLE_ARRAY: Style = Style::new(Color::Green);
-    static ref STYLE_BOOL: Style = Style::new(Color::Red);
-    static ref STYLE_DATETIME: Style = Style::new(Color::Green);
-    static ref STYLE_NUMBER: Style = Style::new(Color::Blue);
-    static ref STYLE_PLAIN: Style = Default::default();
-    static ref STYLE_SPECIAL: Style = Style::new(Color::White).italic();
-    static ref STYLE_STRING: Style = Style::new(Color::Yellow);
-    static ref STYLE_VALUE_COUNT: Style = Style::new(Color::Yellow);
+
+/// Style of the lines written for each value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStyle {
+    /// `.persons[0].name: "Lukas"`
+    Flat,
+    /// `json.persons[0].name = "Lukas";` statements compatible with gron
+    Gron,
+    /// `PERSONS_0_NAME=Lukas` environment variable assignments
+    Env,
+    /// One JSON object per value with path, segments, type, value, and file
+    JsonLines,
+    /// Comma separated file, path, type, and value columns with a header
+    Csv,
+    /// Tab separated file, path, type, and value columns with a header
+    Tsv,
+}
+
+impl OutputStyle {
+    /// Record styles write the type and value of each value as structured record
+    fn is_record(self) -> bool {
+        matches!(self, OutputStyle::JsonLines | OutputStyle::Csv | OutputStyle::Tsv)
+    }
+}
+
+impl FromStr for OutputStyle {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(OutputStyle::Flat),
+            "gron" => Ok(OutputStyle::Gron),
+            "env" => Ok(OutputStyle::Env),
+            "jsonl" => Ok(OutputStyle::JsonLines),
+            "csv" => Ok(OutputStyle::Csv),
+            "tsv" => Ok(OutputStyle::Tsv),
+            _ => Err(crate::error::Error::ParserError {
+                what: s.to_string(),
+                to: "OutputStyle",
+                why: "unknown output style".to_string(),
+            }),
+        }
+    }
+}
+
+/// Syntax of array indices in paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexStyle {
+    /// `.persons[0]`
+    Brackets,
+    /// `.persons.0` using the key separator
+    Separator,
+}
+
+impl FromStr for IndexStyle {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "brackets" => Ok(IndexStyle::Brackets),
+            "separator" => Ok(IndexStyle::Separator),
+            _ => Err(crate::error::Error::ParserError {
+                what: s.to_string(),
+                to: "IndexStyle",
+                why: "unknown index style".to_string(),
+            }),
+        }
+    }
+}
+
+/// When to color the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colors if stdout is a terminal unless `NO_COLOR` is set or `CLICOLOR_FORCE` forces colors
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn is_colorful(self) -> bool {
+        let no_color = std::env::var_os("NO_COLOR").map(|x| !x.is_empty()).unwrap_or(false);
+        let force = std::env::var_os("CLICOLOR_FORCE")
+            .map(|x| !x.is_empty() && x != "0")
+            .unwrap_or(false);
+        self.resolve(no_color, force, io::stdout().is_terminal())
+    }
+
+    fn resolve(self, no_color: bool, force: bool, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto if no_color => false,
+            ColorChoice::Auto => force || is_terminal,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(crate::error::Error::ParserError {
+                what: s.to_string(),
+                to: "ColorChoice",
+                why: "unknown color choice".to_string(),
+            }),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct OutputOpts {
+    style: OutputStyle,
+    path_style: PathStyle,
+    separator: String,
+    index_style: IndexStyle,
+    one_based: bool,
+    root: Option<String>,
     colorful: bool,
+    theme: Theme,
+    depth_colors: bool,
     null: bool,
+    empty: bool,
     quotes: bool,
+    escape: bool,
+    export: bool,
+    header: bool,
+    delimiter: String,
+    null_data: bool,
     numbers: bool,
     end_of_line: bool,
 }
@@ -37,9 +158,23 @@ pub struct OutputOpts {
 impl Default for OutputOpts {
     fn default() -> Self {
         OutputOpts {
+            style: OutputStyle::Flat,
+            path_style: PathStyle::Flat,
+            separator: KEY_SEPARATOR.to_string(),
+            index_style: IndexStyle::Brackets,
+            one_based: false,
+            root: None,
             colorful: true,
+            theme: Theme::default(),
+            depth_colors: false,
             null: true,
+            empty: true,
             quotes: true,
+            escape: true,
+            export: false,
+            header: true,
+            delimiter: ": ".to_string(),
+            null_data: false,
             numbers: false,
             end_of_line: false,
         }
@@ -51,18 +186,88 @@ impl OutputOpts {
         Default::default()
     }
 
+    pub fn with_style(self, style: OutputStyle) -> Self {
+        OutputOpts { style, ..self }
+    }
+
+    pub fn with_path_style(self, path_style: PathStyle) -> Self {
+        OutputOpts { path_style, ..self }
+    }
+
+    pub fn with_separator<T: Into<String>>(self, separator: T) -> Self {
+        OutputOpts {
+            separator: separator.into(),
+            ..self
+        }
+    }
+
+    pub fn with_index_style(self, index_style: IndexStyle) -> Self {
+        OutputOpts { index_style, ..self }
+    }
+
+    /// Counts array indices starting at 1 instead of 0
+    pub fn with_one_based(self, one_based: bool) -> Self {
+        OutputOpts { one_based, ..self }
+    }
+
+    /// Sets the prefix of all paths; an empty prefix drops the leading separator
+    pub fn with_root(self, root: Option<String>) -> Self {
+        OutputOpts { root, ..self }
+    }
+
     pub fn with_color(self, colorful: bool) -> Self {
         OutputOpts { colorful, ..self }
     }
 
+    pub fn with_theme(self, theme: Theme) -> Self {
+        OutputOpts { theme, ..self }
+    }
+
+    /// Colors keys alternating per depth level with the depth styles of the theme
+    pub fn with_depth_colors(self, depth_colors: bool) -> Self {
+        OutputOpts { depth_colors, ..self }
+    }
+
     pub fn with_quotes(self, quotes: bool) -> Self {
         OutputOpts { quotes, ..self }
     }
 
+    /// Escapes newlines and control characters in strings to keep each value on a single line
+    pub fn with_escape(self, escape: bool) -> Self {
+        OutputOpts { escape, ..self }
+    }
+
+    /// Prefixes environment variable assignments with `export `
+    pub fn with_export(self, export: bool) -> Self {
+        OutputOpts { export, ..self }
+    }
+
+    /// Writes a header before the first row of tables
+    pub fn with_header(self, header: bool) -> Self {
+        OutputOpts { header, ..self }
+    }
+
+    /// Sets the delimiter between path and value
+    pub fn with_delimiter<T: Into<String>>(self, delimiter: T) -> Self {
+        OutputOpts {
+            delimiter: delimiter.into(),
+            ..self
+        }
+    }
+
+    /// Terminates each line with NUL instead of newline
+    pub fn with_null_data(self, null_data: bool) -> Self {
+        OutputOpts { null_data, ..self }
+    }
+
     pub fn with_null(self, null: bool) -> Self {
         OutputOpts { null, ..self }
     }
 
+    pub fn with_empty(self, empty: bool) -> Self {
+        OutputOpts { empty, ..self }
+    }
+
     pub fn with_numbers(self, numbers: bool) -> Self {
         OutputOpts { numbers, ..self }
     }
@@ -100,94 +305,607 @@ pub struct OutputWriter {
     inner: Box<dyn Write>,
     opts: OutputOpts,
     value_counter: usize,
+    file: Option<String>,
+    header_written: bool,
+    /// Number of plain lines of the current file
+    plain_lines: usize,
+    /// Collects the paths and values instead of writing them, e.g., for diffs
+    leaves: Option<Vec<(KeyPath, Value)>>,
 }
 
 impl OutputWriter {
     pub fn from_writer(inner: Box<dyn Write>, opts: OutputOpts) -> Self {
-        if !opts.colorful {
-            yansi::Paint::disable();
-        }
         OutputWriter {
             inner,
             opts,
             value_counter: 1,
+            file: None,
+            header_written: false,
+            plain_lines: 0,
+            leaves: None,
         }
     }
 
-    pub fn array(&self, i: usize) -> String {
-        format!("{}{}{}", STYLE_ARRAY.paint("["), i, STYLE_ARRAY.paint("]"))
+    /// Collects the values as passed to the value methods, but without writing them; omits values like `opts` does
+    pub(crate) fn leaf_collector(opts: &OutputOpts) -> Self {
+        OutputWriter {
+            leaves: Some(Vec::new()),
+            ..OutputWriter::from_writer(Box::new(io::sink()), opts.clone())
+        }
+    }
+
+    pub(crate) fn opts(&self) -> &OutputOpts {
+        &self.opts
+    }
+
+    /// Returns the collected paths and values in order
+    pub(crate) fn into_leaves(self) -> Vec<(KeyPath, Value)> {
+        self.leaves.unwrap_or_default()
     }
 
-    pub fn bool<T: Display>(&mut self, path: &str, b: T) {
-        self.writeln(*STYLE_BOOL, path, b);
+    /// Values are written as records or collected
+    fn is_record(&self) -> bool {
+        self.opts.style.is_record() || self.leaves.is_some()
     }
 
-    pub fn datetime<T: Display>(&mut self, path: &str, datetime: T) {
-        self.writeln(*STYLE_DATETIME, path, datetime);
+    /// Sets the name of the file currently written for records
+    pub fn set_file(&mut self, file: Option<String>) {
+        self.file = file;
+        self.plain_lines = 0;
     }
 
-    pub fn number<T: Display>(&mut self, path: &str, number: T) {
-        self.writeln(*STYLE_NUMBER, path, number);
+    pub fn bool<T: Display>(&mut self, path: &KeyPath, b: T) {
+        if self.is_record() {
+            return self.write_record(path, "bool", json_value(b));
+        }
+        self.writeln(self.opts.theme.bool, path, b);
+    }
+
+    pub fn datetime<T: Display>(&mut self, path: &KeyPath, datetime: T) {
+        if self.is_record() {
+            return self.write_record(path, "datetime", Value::String(datetime.to_string()));
+        }
+        match self.opts.style {
+            OutputStyle::Gron => self.writeln(self.opts.theme.datetime, path, json_string(&datetime.to_string())),
+            OutputStyle::Env => self.writeln(self.opts.theme.datetime, path, shell_quote(&datetime.to_string())),
+            _ => self.writeln(self.opts.theme.datetime, path, datetime),
+        }
+    }
+
+    pub fn number<T: Display>(&mut self, path: &KeyPath, number: T) {
+        if self.is_record() {
+            return self.write_record(path, "number", json_value(number));
+        }
+        self.writeln(self.opts.theme.number, path, number);
     }
 
-    pub fn string<T: Display>(&mut self, path: &str, str: T) {
+    pub fn string<T: Display>(&mut self, path: &KeyPath, str: T) {
+        if self.is_record() {
+            self.write_record(path, "string", Value::String(str.to_string()));
+        } else if self.opts.style == OutputStyle::Gron {
+            self.writeln(self.opts.theme.string, path, json_string(&str.to_string()));
+        } else if self.opts.style == OutputStyle::Env {
+            self.writeln(self.opts.theme.string, path, shell_quote(&str.to_string()));
+        } else if self.opts.escape {
+            let str = escape(&str.to_string(), self.opts.quotes).into_owned();
+            self.write_string(path, str);
+        } else {
+            self.write_string(path, str);
+        }
+    }
+
+    fn write_string<T: Display>(&mut self, path: &KeyPath, str: T) {
         if self.opts.quotes {
-            self.writeln(*STYLE_STRING, path, format!("\"{}\"", str));
+            self.writeln(self.opts.theme.string, path, format!("\"{}\"", str));
         } else {
-            self.writeln(*STYLE_STRING, path, str);
+            self.writeln(self.opts.theme.string, path, str);
         }
     }
 
-    pub fn null(&mut self, path: &str) {
-        if self.opts.null {
-            self.special(path, "null")
+    pub fn null(&mut self, path: &KeyPath) {
+        if self.opts.null && self.is_record() {
+            self.write_record(path, "null", Value::Null)
+        } else if self.opts.null && self.opts.style == OutputStyle::Env {
+            self.writeln(self.opts.theme.null, path, shell_quote(""))
+        } else if self.opts.null {
+            self.writeln(self.opts.theme.null, path, "null")
         }
     }
 
-    pub fn special<T: Display>(&mut self, path: &str, str: T) {
-        self.writeln(*STYLE_SPECIAL, path, str);
+    pub fn empty_array(&mut self, path: &KeyPath) {
+        if self.opts.empty && self.is_record() {
+            self.write_record(path, "array", Value::Array(Vec::new()))
+        } else if self.opts.empty {
+            self.special(path, "[]")
+        }
     }
 
+    pub fn empty_object(&mut self, path: &KeyPath) {
+        if self.opts.empty && self.is_record() {
+            self.write_record(path, "object", Value::Object(Map::new()))
+        } else if self.opts.empty {
+            self.special(path, "{}")
+        }
+    }
+
+    /// Declares a non-empty array before its elements; only gron output declares containers
+    pub fn start_array(&mut self, path: &KeyPath) {
+        if self.opts.style == OutputStyle::Gron {
+            self.special(path, "[]")
+        }
+    }
+
+    /// Declares a non-empty object before its members; only gron output declares containers
+    pub fn start_object(&mut self, path: &KeyPath) {
+        if self.opts.style == OutputStyle::Gron {
+            self.special(path, "{}")
+        }
+    }
+
+    pub fn special<T: Display>(&mut self, path: &KeyPath, str: T) {
+        if self.opts.style == OutputStyle::Env {
+            self.writeln(self.opts.theme.special, path, shell_quote(&str.to_string()));
+        } else {
+            self.writeln(self.opts.theme.special, path, str);
+        }
+    }
+
+    /// Writes a line of unstructured input; records and diffs use its line number like `[line 3]` as path
     pub fn plain<T: Display>(&mut self, str: T) {
-        let prefix = prefix(self.opts.numbers, self.value_counter);
+        self.plain_lines += 1;
+        if self.is_record() {
+            let mut path = KeyPath::new();
+            path.push_no_sep(&format!("[line {}]", self.plain_lines));
+            return self.write_record(&path, "text", Value::String(str.to_string()));
+        }
+        let prefix = prefix(&self.opts, self.value_counter);
         let suffix = suffix(self.opts.end_of_line);
         let _ = self.inner.write_fmt(format_args!(
-            "{prefix}{str}{suffix}\n",
+            "{prefix}{str}{suffix}{end}",
             prefix = prefix,
-            str = STYLE_PLAIN.paint(str),
+            str = paint(self.opts.colorful, Style::default(), str),
             suffix = suffix,
+            end = self.end_of_record(),
         ));
         self.value_counter += 1;
     }
 
+    /// Writes a path only present in the right document of a diff
+    pub fn added<T: Display>(&mut self, path: &KeyPath, value: T) {
+        self.write_change(self.opts.theme.added, '+', path, value);
+    }
+
+    /// Writes a path only present in the left document of a diff
+    pub fn removed<T: Display>(&mut self, path: &KeyPath, value: T) {
+        self.write_change(self.opts.theme.removed, '-', path, value);
+    }
+
+    /// Writes a path present in both documents of a diff, but with different values
+    pub fn changed<T: Display, U: Display>(&mut self, path: &KeyPath, left: T, right: U) {
+        self.write_change(
+            self.opts.theme.changed,
+            '~',
+            path,
+            format_args!("{} -> {}", left, right),
+        );
+    }
+
     pub fn reset_value_counter(&mut self) {
         self.value_counter = 0;
     }
 
-    fn writeln<T: Display>(&mut self, style: Style, path: &str, value: T) {
-        let prefix = prefix(self.opts.numbers, self.value_counter);
+    fn end_of_record(&self) -> &'static str {
+        if self.opts.null_data {
+            "\0"
+        } else {
+            "\n"
+        }
+    }
+
+    fn writeln<T: Display>(&mut self, style: Style, path: &KeyPath, value: T) {
+        let prefix = prefix(&self.opts, self.value_counter);
+        let suffix = suffix(self.opts.end_of_line);
+        let _ = match self.opts.style {
+            // Values of record styles are written as records by the value methods
+            OutputStyle::Flat | OutputStyle::JsonLines | OutputStyle::Csv | OutputStyle::Tsv => {
+                self.inner.write_fmt(format_args!(
+                    "{prefix}{path}{delimiter}{value}{suffix}{end}",
+                    prefix = prefix,
+                    path = StyledKeyPath(path, &self.opts, self.opts.colorful),
+                    delimiter = self.opts.delimiter,
+                    value = paint(self.opts.colorful, style, value),
+                    suffix = suffix,
+                    end = self.end_of_record(),
+                ))
+            }
+            OutputStyle::Gron => self.inner.write_fmt(format_args!(
+                "{prefix}{path} = {value};{suffix}{end}",
+                prefix = prefix,
+                path = GronKeyPath(path, &self.opts, self.opts.colorful),
+                value = paint(self.opts.colorful, style, value),
+                suffix = suffix,
+                end = self.end_of_record(),
+            )),
+            OutputStyle::Env => self.inner.write_fmt(format_args!(
+                "{prefix}{export}{name}={value}{suffix}{end}",
+                prefix = prefix,
+                export = if self.opts.export { "export " } else { EMPTY_STR },
+                name = EnvName(path, &self.opts),
+                value = paint(self.opts.colorful, style, value),
+                suffix = suffix,
+                end = self.end_of_record(),
+            )),
+        };
+        self.value_counter += 1;
+    }
+
+    /// Writes path, type, value, and file of a value as JSON object or table row
+    fn write_record(&mut self, path: &KeyPath, value_type: &str, value: Value) {
+        if let Some(leaves) = &mut self.leaves {
+            return leaves.push((path.clone(), value));
+        }
+        let path_str = StyledKeyPath(path, &self.opts, false).to_string();
+        if self.opts.style != OutputStyle::JsonLines {
+            return self.write_row(path_str, value_type, value);
+        }
+
+        let segments: Vec<Value> = path
+            .keys()
+            .iter()
+            .map(|key| match key {
+                Key::Name(name) => Value::String(name.to_string()),
+                Key::Index(i) if self.opts.one_based => Value::from(i + 1),
+                Key::Index(i) => Value::from(*i),
+                Key::Raw(raw) => Value::String(raw.to_string()),
+            })
+            .collect();
+        let record = serde_json::json!({
+            "path": path_str,
+            "segments": segments,
+            "type": value_type,
+            "value": value,
+            "file": self.file,
+        });
+        let _ = serde_json::to_writer(&mut self.inner, &record);
+        let _ = self.inner.write_all(self.end_of_record().as_bytes());
+        self.value_counter += 1;
+    }
+
+    /// Writes a CSV or TSV row; the header is written once before the first row
+    fn write_row(&mut self, path: String, value_type: &str, value: Value) {
+        let (delimiter, quote): (&str, fn(&str) -> Cow<'_, str>) = match self.opts.style {
+            OutputStyle::Tsv => ("\t", tsv_field),
+            _ => (",", csv_field),
+        };
+        if self.opts.header && !self.header_written {
+            let header: &[&str] = if self.opts.numbers {
+                &["line", "file", "path", "type", "value"]
+            } else {
+                &["file", "path", "type", "value"]
+            };
+            let _ = write!(self.inner, "{}{}", header.join(delimiter), self.end_of_record());
+            self.header_written = true;
+        }
+
+        let value = match value {
+            Value::String(x) => x,
+            x => x.to_string(),
+        };
+        let mut fields = Vec::with_capacity(5);
+        if self.opts.numbers {
+            fields.push(Cow::Owned(self.value_counter.to_string()));
+        }
+        fields.push(quote(self.file.as_deref().unwrap_or(EMPTY_STR)));
+        fields.push(quote(&path));
+        fields.push(quote(value_type));
+        fields.push(quote(&value));
+        let _ = write!(self.inner, "{}{}", fields.join(delimiter), self.end_of_record());
+        self.value_counter += 1;
+    }
+
+    fn write_change<T: Display>(&mut self, style: Style, marker: char, path: &KeyPath, value: T) {
+        let prefix = prefix(&self.opts, self.value_counter);
         let suffix = suffix(self.opts.end_of_line);
         let _ = self.inner.write_fmt(format_args!(
-            "{prefix}{path}: {value}{suffix}\n",
+            "{prefix}{change}{delimiter}{value}{suffix}{end}",
             prefix = prefix,
-            path = path,
-            value = style.paint(value),
+            change = paint(
+                self.opts.colorful,
+                style,
+                format_args!("{} {}", marker, StyledKeyPath(path, &self.opts, self.opts.colorful))
+            ),
+            delimiter = self.opts.delimiter,
+            value = value,
             suffix = suffix,
+            end = self.end_of_record(),
         ));
         self.value_counter += 1;
     }
 }
 
-fn prefix(numbers: bool, value_counter: usize) -> Cow<'static, str> {
-    if !numbers {
-        return Cow::Borrowed(EMPTY_STR);
+/// Renders a `KeyPath` in its path style, separator, and index syntax with optionally styled array indices
+struct StyledKeyPath<'a>(&'a KeyPath, &'a OutputOpts, bool);
+
+impl<'a> Display for StyledKeyPath<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let opts = self.1;
+        let colorful = self.2;
+        if opts.path_style == PathStyle::JsonPointer {
+            return Display::fmt(&self.0.json_pointer(), f);
+        }
+
+        if let Some(root) = &opts.root {
+            f.write_str(root)?;
+        }
+        let drop_leading_separator = opts.root.as_deref() == Some("");
+        let mut depth = 0;
+        for (i, key) in self.0.keys().iter().enumerate() {
+            let separator = if i == 0 && drop_leading_separator {
+                EMPTY_STR
+            } else {
+                opts.separator.as_str()
+            };
+            let index = |i: usize| if opts.one_based { i + 1 } else { i };
+            let key_style = key_style(opts, depth);
+            match key {
+                Key::Name(name) if Key::needs_quotes(name, &opts.separator) => {
+                    f.write_fmt(format_args!("[{}]", paint(colorful, key_style, json_string(name))))?
+                }
+                Key::Name(name) => f.write_fmt(format_args!(
+                    "{}{}",
+                    paint(colorful, opts.theme.separator, separator),
+                    paint(colorful, key_style, name)
+                ))?,
+                Key::Index(i) if opts.index_style == IndexStyle::Separator => f.write_fmt(format_args!(
+                    "{}{}",
+                    paint(colorful, opts.theme.index, separator),
+                    index(*i)
+                ))?,
+                Key::Index(i) => f.write_fmt(format_args!(
+                    "{}{}{}",
+                    paint(colorful, opts.theme.index, "["),
+                    index(*i),
+                    paint(colorful, opts.theme.index, "]")
+                ))?,
+                Key::Raw(raw) => f.write_str(raw)?,
+            }
+            if !matches!(key, Key::Raw(_)) {
+                depth += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Quotes CSV fields containing delimiters, quotes, or line breaks according to RFC 4180
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
     }
+}
 
-    let prefix = if numbers {
-        format!("{:>5}  ", STYLE_VALUE_COUNT.paint(value_counter))
+/// Escapes backslashes, tabs, and line breaks in TSV fields which must not contain them
+fn tsv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(['\\', '\t', '\n', '\r']) {
+        let field = field
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        Cow::Owned(field)
     } else {
-        String::new()
-    };
+        Cow::Borrowed(field)
+    }
+}
+
+/// Converts the text of a bool or number into a JSON value; numbers not valid in JSON like `nan` become strings
+fn json_value<T: Display>(value: T) -> Value {
+    let str = value.to_string();
+    serde_json::from_str(&str).unwrap_or(Value::String(str))
+}
+
+/// Escapes backslashes and control characters like JSON strings; double quotes are only escaped if strings are quoted
+fn escape(str: &str, quotes: bool) -> Cow<'_, str> {
+    let needs_escape = |c: char| c == '\\' || c.is_control() || (quotes && c == '"');
+    if !str.contains(needs_escape) {
+        return Cow::Borrowed(str);
+    }
+
+    let mut escaped = String::with_capacity(str.len() + 8);
+    for c in str.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\\' => escaped.push_str("\\\\"),
+            '"' if quotes => escaped.push_str("\\\""),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Renders a `KeyPath` as upper-cased environment variable name like `PERSONS_0_NAME`
+///
+/// Keys and the separator are sanitized by replacing all characters except ASCII letters, digits, and underscores
+/// with underscores.
+struct EnvName<'a>(&'a KeyPath, &'a OutputOpts);
+
+impl<'a> Display for EnvName<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let opts = self.1;
+        let separator = env_sanitize(&opts.separator);
+        let mut name = String::new();
+        if let Some(root) = opts.root.as_deref().filter(|x| !x.is_empty()) {
+            name.push_str(&env_sanitize(root));
+        }
+        for key in self.0.keys() {
+            let key = match key {
+                Key::Name(name) => env_sanitize(name),
+                Key::Index(i) if opts.one_based => (i + 1).to_string(),
+                Key::Index(i) => i.to_string(),
+                Key::Raw(raw) => env_sanitize(raw.trim_start_matches('[').trim_end_matches(']')),
+            };
+            if !name.is_empty() {
+                name.push_str(&separator);
+            }
+            name.push_str(&key);
+        }
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        f.write_str(&name)
+    }
+}
+
+fn env_sanitize(str: &str) -> String {
+    str.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Quotes values for POSIX shells with single quotes unless all characters are safe; empty values stay empty
+fn shell_quote(str: &str) -> Cow<'_, str> {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./:@%+,=".contains(c);
+    if str.chars().all(is_safe) {
+        return Cow::Borrowed(str);
+    }
+
+    Cow::Owned(format!("'{}'", str.replace('\'', "'\\''")))
+}
+
+/// Renders a `KeyPath` as JavaScript expression starting at `json` like gron with optionally styled array indices
+struct GronKeyPath<'a>(&'a KeyPath, &'a OutputOpts, bool);
+
+impl<'a> Display for GronKeyPath<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let opts = self.1;
+        let colorful = self.2;
+        f.write_str("json")?;
+        for (depth, key) in self.0.keys().iter().enumerate() {
+            let key_style = key_style(opts, depth);
+            match key {
+                Key::Name(name) if is_identifier(name) => f.write_fmt(format_args!(
+                    "{}{}",
+                    paint(colorful, opts.theme.separator, "."),
+                    paint(colorful, key_style, name)
+                ))?,
+                Key::Name(name) => f.write_fmt(format_args!("[{}]", paint(colorful, key_style, json_string(name))))?,
+                Key::Index(i) => f.write_fmt(format_args!(
+                    "{}{}{}",
+                    paint(colorful, opts.theme.index, "["),
+                    i,
+                    paint(colorful, opts.theme.index, "]")
+                ))?,
+                // Document and record prefixes like `[doc 1]` become quoted keys
+                Key::Raw(raw) => {
+                    let raw = raw.strip_prefix('[').and_then(|x| x.strip_suffix(']')).unwrap_or(raw);
+                    f.write_fmt(format_args!("[{}]", json_string(raw)))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+static JS_RESERVED_WORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Checks if `name` may be used as JavaScript property accessor like `json.name`
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_' || c == '$');
+    valid_start && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$') && !JS_RESERVED_WORDS.contains(&name)
+}
+
+fn json_string(str: &str) -> String {
+    serde_json::to_string(str).unwrap_or_else(|_| format!("\"{}\"", escape(str, true)))
+}
+
+/// Style of keys at `depth` which alternates between the depth styles of the theme if enabled
+fn key_style(opts: &OutputOpts, depth: usize) -> Style {
+    match &opts.theme.depths {
+        depths if opts.depth_colors && !depths.is_empty() => depths[depth % depths.len()],
+        _ => opts.theme.key,
+    }
+}
+
+/// Paints `value` in `style` if `colorful`; colors are decided per writer instead of globally by `yansi::Paint`
+fn paint<T: Display>(colorful: bool, style: Style, value: T) -> Painted<T> {
+    Painted(if colorful { Some(style) } else { None }, value)
+}
+
+struct Painted<T>(Option<Style>, T);
+
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(style) => Display::fmt(&style.paint(&self.1), f),
+            None => Display::fmt(&self.1, f),
+        }
+    }
+}
+
+fn prefix(opts: &OutputOpts, value_counter: usize) -> Cow<'static, str> {
+    if !opts.numbers {
+        return Cow::Borrowed(EMPTY_STR);
+    }
+
+    let prefix = format!("{:>5}  ", paint(opts.colorful, opts.theme.line_number, value_counter));
 
     Cow::Owned(prefix)
 }
@@ -219,3 +937,163 @@ impl TryFrom<Output> for OutputWriter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use spectral::prelude::*;
+
+    #[test]
+    fn escape_control_characters() {
+        let escaped = escape("line 1\nline 2\t\u{1b}[0m \\ \"quoted\"", true);
+
+        asserting("control characters are escaped")
+            .that(&escaped.as_ref())
+            .is_equal_to(r#"line 1\nline 2\t\u001b[0m \\ \"quoted\""#);
+    }
+
+    #[test]
+    fn gron_key_path() {
+        let mut path = KeyPath::new();
+        path.push("persons");
+        path.push_index(0);
+        path.push("first name");
+        path.push("class");
+        path.push("_id");
+
+        asserting("identifiers are accessed and other keys are quoted")
+            .that(&GronKeyPath(&path, &OutputOpts::new(), false).to_string().as_str())
+            .is_equal_to(r#"json.persons[0]["first name"]["class"]._id"#);
+    }
+
+    #[test]
+    fn styled_key_path() {
+        let mut path = KeyPath::new();
+        path.push("persons");
+        path.push_index(0);
+        path.push("first_name");
+        let opts = OutputOpts::new()
+            .with_separator("__")
+            .with_index_style(IndexStyle::Separator)
+            .with_one_based(true)
+            .with_root(Some(String::new()));
+
+        asserting("custom separator without leading separator")
+            .that(&StyledKeyPath(&path, &opts, false).to_string().as_str())
+            .is_equal_to("persons__1__first_name");
+    }
+
+    #[test]
+    fn styled_key_path_with_root() {
+        let mut path = KeyPath::new();
+        path.push("persons");
+        path.push_index(0);
+        let opts = OutputOpts::new().with_root(Some("$".to_string()));
+
+        asserting("root prefix")
+            .that(&StyledKeyPath(&path, &opts, false).to_string().as_str())
+            .is_equal_to("$.persons[0]");
+    }
+
+    #[test]
+    fn env_name() {
+        let mut path = KeyPath::new();
+        path.push("persons");
+        path.push_index(0);
+        path.push("first-name");
+        let opts = OutputOpts::new().with_separator("__");
+
+        asserting("sanitized and upper-cased name")
+            .that(&EnvName(&path, &opts).to_string().as_str())
+            .is_equal_to("PERSONS__0__FIRST_NAME");
+    }
+
+    #[test]
+    fn shell_quoting() {
+        asserting("safe value")
+            .that(&shell_quote("Lukas").as_ref())
+            .is_equal_to("Lukas");
+        asserting("empty value").that(&shell_quote("").as_ref()).is_equal_to("");
+        asserting("value with single quote")
+            .that(&shell_quote("it's $HOME").as_ref())
+            .is_equal_to(r#"'it'\''s $HOME'"#);
+    }
+
+    #[test]
+    fn csv_quoting() {
+        asserting("plain field")
+            .that(&csv_field("Lukas").as_ref())
+            .is_equal_to("Lukas");
+        asserting("field with delimiter and quotes")
+            .that(&csv_field("say \"hi\", Lukas").as_ref())
+            .is_equal_to("\"say \"\"hi\"\", Lukas\"");
+    }
+
+    #[test]
+    fn tsv_escaping() {
+        asserting("field with tab and newline")
+            .that(&tsv_field("a\tb\nc").as_ref())
+            .is_equal_to("a\\tb\\nc");
+    }
+
+    #[test]
+    fn color_choice() {
+        asserting("auto on terminal")
+            .that(&ColorChoice::Auto.resolve(false, false, true))
+            .is_true();
+        asserting("auto in pipe")
+            .that(&ColorChoice::Auto.resolve(false, false, false))
+            .is_false();
+        asserting("auto with NO_COLOR")
+            .that(&ColorChoice::Auto.resolve(true, true, true))
+            .is_false();
+        asserting("auto with CLICOLOR_FORCE")
+            .that(&ColorChoice::Auto.resolve(false, true, false))
+            .is_true();
+        asserting("always with NO_COLOR")
+            .that(&ColorChoice::Always.resolve(true, false, false))
+            .is_true();
+    }
+
+    #[test]
+    fn colors_are_decided_per_writer() {
+        let _plain = OutputWriter::from_writer(Box::new(io::sink()), OutputOpts::new().with_color(false));
+
+        asserting("colors are still painted")
+            .that(&paint(true, Theme::default().string, "Lukas").to_string().as_str())
+            .is_equal_to("\u{1b}[33mLukas\u{1b}[0m");
+        asserting("no colors")
+            .that(&paint(false, Theme::default().string, "Lukas").to_string().as_str())
+            .is_equal_to("Lukas");
+    }
+
+    #[test]
+    fn depth_colors() {
+        let mut path = KeyPath::new();
+        path.push("persons");
+        path.push_index(0);
+        path.push("name");
+        path.push("first_name");
+        let theme = Theme::default()
+            .with_colors("depth=31,32:separator=2")
+            .expect("failed to apply colors");
+        let opts = OutputOpts::new().with_theme(theme).with_depth_colors(true);
+
+        asserting("keys alternate per depth")
+            .that(&StyledKeyPath(&path, &opts, true).to_string().as_str())
+            .is_equal_to(
+                "\u{1b}[2m.\u{1b}[0m\u{1b}[31mpersons\u{1b}[0m\u{1b}[32m[\u{1b}[0m0\u{1b}[32m]\u{1b}[0m\
+                 \u{1b}[2m.\u{1b}[0m\u{1b}[31mname\u{1b}[0m\u{1b}[2m.\u{1b}[0m\u{1b}[32mfirst_name\u{1b}[0m",
+            );
+    }
+
+    #[test]
+    fn escape_without_quotes() {
+        let escaped = escape("say \"hi\"\n", false);
+
+        asserting("double quotes are kept")
+            .that(&escaped.as_ref())
+            .is_equal_to("say \"hi\"\\n");
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use yansi::{Color, Style};
+
+use crate::{Error, Result};
+
+/// Environment variable overriding styles like `LS_COLORS`, e.g., `number=1;36:string=33`
+pub static FCAT_COLORS: &str = "FCAT_COLORS";
+/// Environment variable setting the path of the config file
+pub static FCAT_CONFIG: &str = "FCAT_CONFIG";
+
+/// Styles of the elements of the output
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub key: Style,
+    pub separator: Style,
+    /// Styles of keys alternating per depth level, if enabled
+    pub depths: Vec<Style>,
+    pub index: Style,
+    pub string: Style,
+    pub number: Style,
+    pub bool: Style,
+    pub datetime: Style,
+    pub null: Style,
+    /// Style of empty arrays and objects and of containers declared by gron output
+    pub special: Style,
+    pub line_number: Style,
+    pub added: Style,
+    pub removed: Style,
+    pub changed: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            key: Style::default(),
+            separator: Style::default(),
+            depths: vec![
+                Style::new(Color::Blue),
+                Style::new(Color::Magenta),
+                Style::new(Color::Cyan),
+            ],
+            index: Style::new(Color::Green),
+            string: Style::new(Color::Yellow),
+            number: Style::new(Color::Blue),
+            bool: Style::new(Color::Red),
+            datetime: Style::new(Color::Green),
+            null: Style::new(Color::White).italic(),
+            special: Style::new(Color::White).italic(),
+            line_number: Style::new(Color::Yellow),
+            added: Style::new(Color::Green),
+            removed: Style::new(Color::Red),
+            changed: Style::new(Color::Yellow),
+        }
+    }
+}
+
+impl Theme {
+    /// Bright colors for terminals with dark backgrounds
+    pub fn dark() -> Self {
+        Theme {
+            key: Style::new(Color::White).bold(),
+            separator: Style::new(Color::Fixed(245)),
+            index: Style::new(Color::Green),
+            string: Style::new(Color::Yellow),
+            number: Style::new(Color::Cyan),
+            bool: Style::new(Color::Magenta),
+            datetime: Style::new(Color::Green),
+            null: Style::new(Color::Fixed(245)).italic(),
+            special: Style::new(Color::Fixed(245)),
+            line_number: Style::new(Color::Fixed(245)),
+            ..Theme::default()
+        }
+    }
+
+    /// Dark colors for terminals with light backgrounds
+    pub fn light() -> Self {
+        Theme {
+            key: Style::new(Color::Black).bold(),
+            separator: Style::new(Color::Fixed(242)),
+            index: Style::new(Color::Cyan),
+            string: Style::new(Color::Magenta),
+            number: Style::new(Color::Blue),
+            bool: Style::new(Color::Red),
+            datetime: Style::new(Color::Green),
+            null: Style::new(Color::Fixed(242)).italic(),
+            special: Style::new(Color::Fixed(242)),
+            line_number: Style::new(Color::Fixed(242)),
+            ..Theme::default()
+        }
+    }
+
+    /// Colors of the Solarized palette
+    pub fn solarized() -> Self {
+        Theme {
+            key: Style::new(Color::RGB(0x26, 0x8b, 0xd2)),
+            separator: Style::new(Color::RGB(0x58, 0x6e, 0x75)),
+            depths: vec![
+                Style::new(Color::RGB(0x26, 0x8b, 0xd2)),
+                Style::new(Color::RGB(0x6c, 0x71, 0xc4)),
+                Style::new(Color::RGB(0x2a, 0xa1, 0x98)),
+            ],
+            index: Style::new(Color::RGB(0xb5, 0x89, 0x00)),
+            string: Style::new(Color::RGB(0x2a, 0xa1, 0x98)),
+            number: Style::new(Color::RGB(0xd3, 0x36, 0x82)),
+            bool: Style::new(Color::RGB(0xcb, 0x4b, 0x16)),
+            datetime: Style::new(Color::RGB(0x6c, 0x71, 0xc4)),
+            null: Style::new(Color::RGB(0x58, 0x6e, 0x75)).italic(),
+            special: Style::new(Color::RGB(0x93, 0xa1, 0xa1)),
+            line_number: Style::new(Color::RGB(0x58, 0x6e, 0x75)),
+            added: Style::new(Color::RGB(0x85, 0x99, 0x00)),
+            removed: Style::new(Color::RGB(0xdc, 0x32, 0x2f)),
+            changed: Style::new(Color::RGB(0xb5, 0x89, 0x00)),
+        }
+    }
+
+    /// Loads the theme `name` or the theme of the config file and applies the styles of the config file and `FCAT_COLORS`
+    pub fn load(name: Option<&str>) -> Result<Theme> {
+        let config = read_config()?;
+        let section = config
+            .as_ref()
+            .and_then(|x| x.get("theme"))
+            .and_then(toml::Value::as_table);
+
+        let config_name = section.and_then(|x| x.get("name")).and_then(toml::Value::as_str);
+        let mut theme = match name.or(config_name) {
+            Some(name) => Theme::from_str(name)?,
+            None => Theme::default(),
+        };
+        if let Some(section) = section {
+            for (element, spec) in section.iter().filter(|(k, _)| k.as_str() != "name") {
+                let spec = spec
+                    .as_str()
+                    .ok_or_else(|| theme_error(element, "style must be a string"))?;
+                theme = theme.with_style(element, spec)?;
+            }
+        }
+        if let Ok(colors) = env::var(FCAT_COLORS) {
+            theme = theme.with_colors(&colors)?;
+        }
+
+        Ok(theme)
+    }
+
+    /// Applies styles like `number=1;36:string=33` with ANSI SGR codes as used by `LS_COLORS`
+    pub fn with_colors(self, colors: &str) -> Result<Theme> {
+        let mut theme = self;
+        for entry in colors.split(':').filter(|x| !x.is_empty()) {
+            let (element, spec) = entry
+                .split_once('=')
+                .ok_or_else(|| theme_error(entry, "missing '=' between element and style"))?;
+            theme = theme.with_style(element, spec)?;
+        }
+
+        Ok(theme)
+    }
+
+    /// Sets the style of `element` to ANSI SGR codes like `1;36`; `depth` takes a comma separated list of styles
+    pub fn with_style(self, element: &str, spec: &str) -> Result<Theme> {
+        let mut theme = self;
+        if element == "depth" {
+            theme.depths = spec.split(',').map(parse_style).collect::<Result<_>>()?;
+            return Ok(theme);
+        }
+
+        let style = parse_style(spec)?;
+        match element {
+            "key" => theme.key = style,
+            "separator" => theme.separator = style,
+            "index" => theme.index = style,
+            "string" => theme.string = style,
+            "number" => theme.number = style,
+            "bool" => theme.bool = style,
+            "datetime" => theme.datetime = style,
+            "null" => theme.null = style,
+            "special" => theme.special = style,
+            "line" => theme.line_number = style,
+            "added" => theme.added = style,
+            "removed" => theme.removed = style,
+            "changed" => theme.changed = style,
+            _ => return Err(theme_error(element, "unknown element")),
+        }
+
+        Ok(theme)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Theme::default()),
+            "dark" => Ok(Theme::dark()),
+            "light" => Ok(Theme::light()),
+            "solarized" => Ok(Theme::solarized()),
+            _ => Err(theme_error(s, "unknown theme")),
+        }
+    }
+}
+
+/// Reads `$FCAT_CONFIG`, `$XDG_CONFIG_HOME/fcat/config.toml`, or `$HOME/.config/fcat/config.toml` if it exists
+fn read_config() -> Result<Option<toml::Value>> {
+    let path = env::var_os(FCAT_CONFIG).map(PathBuf::from).or_else(|| {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+            .map(|x| x.join("fcat").join("config.toml"))
+    });
+    let path = match path {
+        Some(path) if path.is_file() => path,
+        _ => return Ok(None),
+    };
+
+    let config = fs::read_to_string(path)?;
+    Ok(Some(toml::from_str(&config)?))
+}
+
+fn parse_style(spec: &str) -> Result<Style> {
+    let mut codes = spec
+        .split(';')
+        .map(|x| x.parse::<u8>().map_err(|_| theme_error(spec, "invalid code")));
+    let mut style = Style::default();
+
+    while let Some(code) = codes.next() {
+        style = match code? {
+            0 => Style::default(),
+            1 => style.bold(),
+            2 => style.dimmed(),
+            3 => style.italic(),
+            4 => style.underline(),
+            5 => style.blink(),
+            7 => style.invert(),
+            8 => style.hidden(),
+            9 => style.strikethrough(),
+            x @ 30..=37 => style.fg(ansi_color(x - 30)),
+            x @ 40..=47 => style.bg(ansi_color(x - 40)),
+            x @ 90..=97 => style.fg(Color::Fixed(x - 90 + 8)),
+            x @ 100..=107 => style.bg(Color::Fixed(x - 100 + 8)),
+            x @ 38 | x @ 48 => {
+                let color = match codes.next().transpose()? {
+                    Some(5) => Color::Fixed(next_code(&mut codes, spec)?),
+                    Some(2) => Color::RGB(
+                        next_code(&mut codes, spec)?,
+                        next_code(&mut codes, spec)?,
+                        next_code(&mut codes, spec)?,
+                    ),
+                    _ => return Err(theme_error(spec, "extended color must be '5;n' or '2;r;g;b'")),
+                };
+                if x == 38 {
+                    style.fg(color)
+                } else {
+                    style.bg(color)
+                }
+            }
+            _ => return Err(theme_error(spec, "unsupported code")),
+        }
+    }
+
+    Ok(style)
+}
+
+fn next_code<I: Iterator<Item = Result<u8>>>(codes: &mut I, spec: &str) -> Result<u8> {
+    codes
+        .next()
+        .unwrap_or_else(|| Err(theme_error(spec, "incomplete extended color")))
+}
+
+fn ansi_color(code: u8) -> Color {
+    match code {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::White,
+    }
+}
+
+fn theme_error(what: &str, why: &str) -> Error {
+    Error::ParserError {
+        what: what.to_string(),
+        to: "Theme",
+        why: why.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use spectral::prelude::*;
+
+    #[test]
+    fn parse_styles() {
+        asserting("bold cyan")
+            .that(&parse_style("1;36").ok())
+            .is_equal_to(Some(Style::new(Color::Cyan).bold()));
+        asserting("256 colors")
+            .that(&parse_style("38;5;208").ok())
+            .is_equal_to(Some(Style::new(Color::Fixed(208))));
+        asserting("true colors on background")
+            .that(&parse_style("48;2;0;43;54").ok())
+            .is_equal_to(Some(Style::default().bg(Color::RGB(0, 43, 54))));
+        asserting("incomplete extended color")
+            .that(&parse_style("38;5").is_err())
+            .is_true();
+    }
+
+    #[test]
+    fn with_colors() {
+        let theme = Theme::default()
+            .with_colors("number=1;36:key=34")
+            .expect("failed to apply colors");
+
+        asserting("number style")
+            .that(&theme.number)
+            .is_equal_to(Style::new(Color::Cyan).bold());
+        asserting("key style")
+            .that(&theme.key)
+            .is_equal_to(Style::new(Color::Blue));
+        asserting("unchanged string style")
+            .that(&theme.string)
+            .is_equal_to(Theme::default().string);
+    }
+
+    #[test]
+    fn special_is_independent_of_null() {
+        let theme = Theme::default()
+            .with_colors("special=2")
+            .expect("failed to apply colors");
+
+        asserting("special style")
+            .that(&theme.special)
+            .is_equal_to(Style::default().dimmed());
+        asserting("unchanged null style")
+            .that(&theme.null)
+            .is_equal_to(Theme::default().null);
+    }
+
+    #[test]
+    fn unknown_element() {
+        let res = Theme::default().with_colors("numbers=36");
+
+        asserting("unknown element fails").that(&res.is_err()).is_true();
+    }
+}
@@ -0,0 +1,450 @@
+// Copyright 2021 Lukas Pustina <lukas@pustina.de>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::io::{BufRead, BufReader, Write};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::catter::{Key, KeyPath};
+use crate::input::InputReader;
+use crate::{Error, Format, Input, Result};
+
+/// Maximum number of missing elements an array index may skip, e.g., of elements dropped by `--exclude`
+const MAX_INDEX_GAP: usize = 1 << 16;
+
+/// Key of the single entry tables `toml` deserializes into datetimes
+const TOML_DATETIME: &str = "$__toml_private_datetime";
+
+/// Rebuilds documents from flattened `<path>: <value>` lines as written by `OutputWriter`
+///
+/// Lines prefixed like `[doc 1]` or `[Deployment/nginx]` are collected into separate documents in order of their
+/// first appearance, so multi-document YAML streams can be rebuilt.
+#[derive(Debug)]
+pub struct UnflatCat {
+    to: Format,
+    /// Documents by their prefix; lines without prefix belong to the document without prefix
+    documents: Vec<(Option<String>, Value)>,
+}
+
+impl UnflatCat {
+    pub fn new(to: Format) -> Self {
+        UnflatCat {
+            to,
+            documents: Vec::new(),
+        }
+    }
+
+    /// Reads all flattened lines of `input` and merges them into the document
+    pub fn read(&mut self, input: Input) -> Result<()> {
+        let reader: InputReader = input.try_into()?;
+        let buf_reader = BufReader::new(reader);
+        for line in buf_reader.lines() {
+            let line = line?;
+            let line = strip_ansi_codes(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (path, value) = split_line(&line)?;
+            let value = match self.to {
+                Format::Toml => parse_toml_value(value),
+                _ => parse_value(value),
+            };
+            let (prefix, keys) = match path.keys().split_first() {
+                Some((Key::Raw(raw), keys)) => (Some(raw.as_str()), keys),
+                _ => (None, path.keys()),
+            };
+            let document = self.document(prefix);
+            insert(document, keys, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn document(&mut self, prefix: Option<&str>) -> &mut Value {
+        let index = match self.documents.iter().position(|(x, _)| x.as_deref() == prefix) {
+            Some(index) => index,
+            None => {
+                self.documents.push((prefix.map(str::to_string), Value::Null));
+                self.documents.len() - 1
+            }
+        };
+        &mut self.documents[index].1
+    }
+
+    fn values(&self) -> Vec<&Value> {
+        if self.documents.is_empty() {
+            return vec![&Value::Null];
+        }
+        self.documents.iter().map(|(_, x)| x).collect()
+    }
+
+    /// Serializes the documents in the target format; multiple documents are written as a stream of documents
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<()> {
+        let documents = self.values();
+        match self.to {
+            Format::Json => {
+                for document in documents {
+                    serde_json::to_writer_pretty(&mut writer, document)?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            Format::JsonLines => {
+                // Records are either documents like `[record 1]` or the elements of a single top level array
+                let records = match documents.as_slice() {
+                    [Value::Array(x)] if self.documents.iter().all(|(prefix, _)| prefix.is_none()) => {
+                        x.iter().collect()
+                    }
+                    _ => documents,
+                };
+                for record in records {
+                    serde_json::to_writer(&mut writer, record)?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            Format::Toml if documents.len() > 1 => {
+                return Err(Error::ParserError {
+                    what: format!("{} documents", documents.len()),
+                    to: "Toml",
+                    why: "multiple documents cannot be written as a single TOML document".to_string(),
+                })
+            }
+            Format::Toml => {
+                // TOML has no null, so null values are left out
+                let toml = toml::Value::deserialize(without_nulls(documents[0]))?;
+                let str = toml::to_string(&toml)?;
+                writer.write_all(str.as_bytes())?;
+            }
+            Format::Yaml => {
+                for document in documents {
+                    serde_yaml::to_writer(&mut writer, document)?;
+                }
+            }
+            Format::Csv
+            | Format::Dotenv
+            | Format::Hcl
+            | Format::Ini
+            | Format::Properties
+            | Format::Tsv
+            | Format::Xml => {
+                return Err(Error::ParserError {
+                    what: self.to.to_string(),
+                    to: "Format",
+                    why: "unflattening to this format is not supported".to_string(),
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the document; multiple documents are returned as array
+    pub fn into_value(mut self) -> Value {
+        match self.documents.len() {
+            0 => Value::Null,
+            1 => self.documents.remove(0).1,
+            _ => Value::Array(self.documents.into_iter().map(|(_, x)| x).collect()),
+        }
+    }
+}
+
+/// Removes null values from objects and arrays recursively
+fn without_nulls(value: &Value) -> Value {
+    match value {
+        Value::Array(x) => Value::Array(x.iter().filter(|x| !x.is_null()).map(without_nulls).collect()),
+        Value::Object(x) => Value::Object(
+            x.iter()
+                .filter(|(_, x)| !x.is_null())
+                .map(|(k, x)| (k.clone(), without_nulls(x)))
+                .collect(),
+        ),
+        x => x.clone(),
+    }
+}
+
+/// Parses a single flattened line into its path and value
+pub fn parse_line(line: &str) -> Result<(KeyPath, Value)> {
+    let (path, value) = split_line(line)?;
+
+    Ok((path, parse_value(value)))
+}
+
+fn split_line(line: &str) -> Result<(KeyPath, &str)> {
+    let (path, rest) = KeyPath::parse_prefix(line)?;
+    let value = rest.strip_prefix(": ").ok_or_else(|| Error::ParserError {
+        what: line.to_string(),
+        to: "flattened line",
+        why: "missing ': ' between path and value".to_string(),
+    })?;
+
+    Ok((path, value))
+}
+
+/// Parses a value literal; unquoted text that is neither null, a bool, nor a number is taken as string
+fn parse_value(value: &str) -> Value {
+    match value {
+        "null" => return Value::Null,
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        "[]" => return Value::Array(Vec::new()),
+        "{}" => return Value::Object(Map::new()),
+        _ => {}
+    }
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        // Strings are escaped like JSON strings unless escaping has been disabled
+        let str = serde_json::from_str::<String>(value).unwrap_or_else(|_| value[1..value.len() - 1].to_string());
+        return Value::String(str);
+    }
+    if let Ok(x) = value.parse::<i64>() {
+        return Value::from(x);
+    }
+    if let Ok(x) = value.parse::<u64>() {
+        return Value::from(x);
+    }
+    if let Some(x) = value.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+        return Value::Number(x);
+    }
+
+    Value::String(value.to_string())
+}
+
+/// Parses a value literal like `parse_value`, but keeps unquoted RFC 3339 dates and times as TOML datetimes
+fn parse_toml_value(value: &str) -> Value {
+    if value.parse::<toml::value::Datetime>().is_err() {
+        return parse_value(value);
+    }
+    let mut datetime = Map::new();
+    datetime.insert(TOML_DATETIME.to_string(), Value::from(value));
+
+    Value::Object(datetime)
+}
+
+fn insert(node: &mut Value, keys: &[Key], value: Value) -> Result<()> {
+    let (key, rest) = match keys.split_first() {
+        Some(x) => x,
+        None => {
+            *node = value;
+            return Ok(());
+        }
+    };
+
+    match key {
+        Key::Name(name) => {
+            if !node.is_object() {
+                *node = Value::Object(Map::new());
+            }
+            let map = node.as_object_mut().expect("node has just been set to object");
+            let child = map.entry(name.as_str()).or_insert(Value::Null);
+            insert(child, rest, value)
+        }
+        Key::Index(i) => {
+            if !node.is_array() {
+                *node = Value::Array(Vec::new());
+            }
+            let array = node.as_array_mut().expect("node has just been set to array");
+            if *i > array.len() + MAX_INDEX_GAP {
+                return Err(Error::ParserError {
+                    what: format!("[{}]", i),
+                    to: "document",
+                    why: format!("index is too far past array length {}", array.len()),
+                });
+            }
+            if array.len() <= *i {
+                array.resize(i + 1, Value::Null);
+            }
+            insert(&mut array[*i], rest, value)
+        }
+        Key::Raw(raw) => Err(Error::ParserError {
+            what: raw.to_string(),
+            to: "document",
+            why: "raw path elements cannot be unflattened".to_string(),
+        }),
+    }
+}
+
+/// Removes ANSI escape sequences so colored output can be unflattened, too
+fn strip_ansi_codes(line: &str) -> Cow<'_, str> {
+    if !line.contains('\u{1b}') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut res = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip CSI sequences like "\x1b[3;37m" up to and including the final byte
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            res.push(c);
+        }
+    }
+
+    Cow::Owned(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use spectral::prelude::*;
+
+    fn unflatten(lines: &'static str) -> Value {
+        let mut unflatcat = UnflatCat::new(Format::Json);
+        unflatcat
+            .read(Input::from_reader(lines.as_bytes()))
+            .expect("failed to unflatten");
+        unflatcat.into_value()
+    }
+
+    #[test]
+    fn parse_line_with_nested_path() {
+        let (path, value) = parse_line(".persons[0].name: \"Lukas\"").expect("failed to parse line");
+
+        asserting("path")
+            .that(&path.to_string().as_str())
+            .is_equal_to(".persons[0].name");
+        asserting("value").that(&value).is_equal_to(Value::from("Lukas"));
+    }
+
+    #[test]
+    fn parse_line_without_value() {
+        let res = parse_line(".persons[0].name");
+
+        asserting("line without value fails").that(&res).is_err();
+    }
+
+    #[test]
+    fn parse_values() {
+        asserting("null").that(&parse_value("null")).is_equal_to(Value::Null);
+        asserting("bool")
+            .that(&parse_value("true"))
+            .is_equal_to(Value::Bool(true));
+        asserting("integer")
+            .that(&parse_value("-42"))
+            .is_equal_to(Value::from(-42));
+        asserting("float")
+            .that(&parse_value("1.5"))
+            .is_equal_to(Value::from(1.5));
+        asserting("empty array")
+            .that(&parse_value("[]"))
+            .is_equal_to(Value::Array(Vec::new()));
+        asserting("empty object")
+            .that(&parse_value("{}"))
+            .is_equal_to(Value::Object(Map::new()));
+        asserting("quoted string")
+            .that(&parse_value("\"42\""))
+            .is_equal_to(Value::from("42"));
+        asserting("escaped string")
+            .that(&parse_value(r#""line 1\nline \"2\"""#))
+            .is_equal_to(Value::from("line 1\nline \"2\""));
+        asserting("unquoted string")
+            .that(&parse_value("1970-01-01T07:32:00Z"))
+            .is_equal_to(Value::from("1970-01-01T07:32:00Z"));
+    }
+
+    #[test]
+    fn rebuild_document() {
+        let lines = ".file_type: \"json\"\n.persons[0].age: 42\n.persons[0].name.first_name: \"Lukas\"\n.persons[1].phone: null\n";
+
+        let value = unflatten(lines);
+
+        let expected = serde_json::json!({
+            "file_type": "json",
+            "persons": [
+                { "age": 42, "name": { "first_name": "Lukas" } },
+                { "phone": null }
+            ]
+        });
+        asserting("document is rebuilt").that(&value).is_equal_to(expected);
+    }
+
+    #[test]
+    fn rebuild_document_with_quoted_keys() {
+        let lines = ".metadata.labels[\"app.kubernetes.io/name\"]: \"nginx\"\n.metadata.labels[\"[0]\"]: \"x\"\n";
+
+        let value = unflatten(lines);
+
+        let expected =
+            serde_json::json!({ "metadata": { "labels": { "app.kubernetes.io/name": "nginx", "[0]": "x" } } });
+        asserting("quoted keys are rebuilt").that(&value).is_equal_to(expected);
+    }
+
+    #[test]
+    fn rebuild_document_from_colored_lines() {
+        let lines = ".persons\u{1b}[32m[\u{1b}[0m0\u{1b}[32m]\u{1b}[0m.age: \u{1b}[34m42\u{1b}[0m\n";
+
+        let value = unflatten(lines);
+
+        let expected = serde_json::json!({ "persons": [ { "age": 42 } ] });
+        asserting("ANSI codes are ignored").that(&value).is_equal_to(expected);
+    }
+
+    #[test]
+    fn rebuild_documents_with_prefixes() {
+        let lines = "[doc 0].a: 1\n[doc 1].b: 2\n[doc 0].c: 3\n";
+
+        let value = unflatten(lines);
+
+        let expected = serde_json::json!([{ "a": 1, "c": 3 }, { "b": 2 }]);
+        asserting("documents are rebuilt").that(&value).is_equal_to(expected);
+    }
+
+    #[test]
+    fn rebuild_document_with_too_large_index() {
+        for lines in &[".a[18446744073709551615]: 1\n", ".a[100000000000]: 1\n"] {
+            let mut unflatcat = UnflatCat::new(Format::Json);
+
+            let res = unflatcat.read(Input::from_reader(lines.as_bytes()));
+
+            asserting(lines).that(&res).is_err();
+        }
+    }
+
+    #[test]
+    fn write_toml_without_nulls() {
+        let mut unflatcat = UnflatCat::new(Format::Toml);
+        unflatcat
+            .read(Input::from_reader(
+                ".a: 1\n.b: null\n.c[0]: null\n.c[1]: 2\n".as_bytes(),
+            ))
+            .expect("failed to unflatten");
+        let mut toml = Vec::new();
+
+        let res = unflatcat.write(&mut toml);
+
+        asserting("toml is written").that(&res).is_ok();
+        asserting("null values are left out")
+            .that(&String::from_utf8_lossy(&toml).as_ref())
+            .is_equal_to("a = 1\nc = [2]\n");
+    }
+
+    #[test]
+    fn write_toml_with_datetimes() {
+        let mut unflatcat = UnflatCat::new(Format::Toml);
+        unflatcat
+            .read(Input::from_reader(
+                ".birth_day: 1970-01-01T07:32:00Z\n.quoted: \"1970-01-01\"\n".as_bytes(),
+            ))
+            .expect("failed to unflatten");
+        let mut toml = Vec::new();
+
+        let res = unflatcat.write(&mut toml);
+
+        asserting("toml is written").that(&res).is_ok();
+        asserting("unquoted datetimes are kept")
+            .that(&String::from_utf8_lossy(&toml).as_ref())
+            .is_equal_to("birth_day = 1970-01-01T07:32:00Z\nquoted = \"1970-01-01\"\n");
+    }
+}
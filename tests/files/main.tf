@@ -0,0 +1,19 @@
+variable "name" {
+  default = "flatcat"
+}
+
+resource "aws_instance" "web" {
+  ami           = "ami-0a1b2c3d"
+  instance_type = var.instance_type
+  count         = 2
+  tags = {
+    Name = "${var.name}-web"
+  }
+
+  ebs_block_device {
+    device_name = "/dev/sdb"
+  }
+  ebs_block_device {
+    device_name = "/dev/sdc"
+  }
+}